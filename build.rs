@@ -1,7 +1,17 @@
-use std::{env, fs, path::PathBuf};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
 
 const SHADER_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/shaders");
+/// C++ side of `automation`, using imgui internals that have no C binding.
+const IMGUI_INTERNALS: &str = "src/automation.cpp";
 const SHADER_EXTENSIONS: &[&str] = &["vert", "frag"];
+/// Additional compilations of a shader with preprocessor definitions: (source, variant name, definitions)
+const SHADER_VARIANTS: &[(&str, &str, &[&str])] = &[
+    ("imgui.frag", "imgui_view.frag", &["IMAGE_VIEW"]),
+    ("imgui.frag", "imgui_view_array.frag", &["IMAGE_VIEW", "TEXTURE_ARRAY"]),
+];
 
 fn main() {
     let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
@@ -40,25 +50,63 @@ fn main() {
             continue;
         }
 
-        let name = path.file_name().unwrap().to_str().unwrap().to_string() + ".spv";
-        let dest_path = out_dir.join(name);
+        let name = path.file_name().unwrap().to_str().unwrap().to_string();
+        compile(&compiler, &options, &path, &out_dir.join(name + ".spv"));
 
-        println!("{}", path.display());
-        let bin = compiler
-            .compile_into_spirv(
-                &fs::read_to_string(&path).unwrap(),
-                shaderc::ShaderKind::InferFromSource,
-                &path.display().to_string(),
-                "main",
-                Some(&options),
-            )
-            .unwrap();
+        println!("cargo:rerun-if-changed={}", path.to_str().unwrap());
+    }
 
-        fs::write(dest_path, bin.as_binary_u8()).unwrap();
+    for (source, variant, definitions) in SHADER_VARIANTS {
+        let mut variant_options = options.clone();
+        for definition in definitions.iter() {
+            variant_options.add_macro_definition(definition, None);
+        }
 
-        println!("cargo:rerun-if-changed={}", path.to_str().unwrap());
+        let path = PathBuf::from(SHADER_DIR).join(source);
+        compile(
+            &compiler,
+            &variant_options,
+            &path,
+            &out_dir.join(variant.to_string() + ".spv"),
+        );
     }
 
+    compile_imgui_internals();
+
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rerun-if-changed=shaders");
 }
+
+/// Builds the imgui internals `automation` uses, against the imgui sources and defines of imgui-sys.
+fn compile_imgui_internals() {
+    let imgui_dir = PathBuf::from(env::var_os("DEP_IMGUI_THIRD_PARTY").unwrap()).join("imgui");
+
+    let mut build = cc::Build::new();
+    build.cpp(true).flag_if_supported("-std=c++11").include(imgui_dir);
+    for (key, value) in env::vars() {
+        if let Some(definition) = key.strip_prefix("DEP_IMGUI_DEFINE_") {
+            build.define(definition, (!value.is_empty()).then_some(value.as_str()));
+        }
+    }
+
+    build
+        .warnings(false)
+        .file(IMGUI_INTERNALS)
+        .compile("imgui_sdl3_internals");
+    println!("cargo:rerun-if-changed={IMGUI_INTERNALS}");
+}
+
+fn compile(compiler: &shaderc::Compiler, options: &shaderc::CompileOptions, path: &Path, dest_path: &Path) {
+    println!("{}", path.display());
+    let bin = compiler
+        .compile_into_spirv(
+            &fs::read_to_string(path).unwrap(),
+            shaderc::ShaderKind::InferFromSource,
+            &path.display().to_string(),
+            "main",
+            Some(options),
+        )
+        .unwrap();
+
+    fs::write(dest_path, bin.as_binary_u8()).unwrap();
+}
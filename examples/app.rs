@@ -0,0 +1,47 @@
+use imgui_sdl3::{
+    app::{App, AppConfig, AppContext},
+    builder::ImGuiSdl3Builder,
+};
+use sdl3::pixels::Color;
+
+/// A small tool built on the `App` runner, without writing the frame loop.
+struct Counter {
+    count: u32,
+}
+
+impl App for Counter {
+    fn config(&self) -> AppConfig {
+        AppConfig::new("Counter").with_size(640, 480)
+    }
+
+    fn configure<'a>(&self, builder: ImGuiSdl3Builder<'a>) -> ImGuiSdl3Builder<'a> {
+        // disable creation of files on disc
+        builder.with_ini_filename(None).with_log_filename(None)
+    }
+
+    fn ui(&mut self, ctx: &mut AppContext, ui: &mut imgui::Ui) {
+        ui.window("Counter").build(|| {
+            ui.text(format!("Clicked {} times", self.count));
+
+            if ui.button("Click me") {
+                self.count += 1;
+            }
+
+            let mut color = ctx.clear_color().rgb();
+            let mut rgb = [color.0, color.1, color.2].map(|c| c as f32 / 255.0);
+            if ui.color_edit3("Background", &mut rgb) {
+                let [r, g, b] = rgb.map(|c| (c * 255.0) as u8);
+                color = (r, g, b);
+                ctx.set_clear_color(Color::RGB(color.0, color.1, color.2));
+            }
+
+            if ui.button("Quit") {
+                ctx.quit();
+            }
+        });
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    imgui_sdl3::run(Counter { count: 0 })
+}
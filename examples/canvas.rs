@@ -0,0 +1,53 @@
+use imgui_sdl3::{ImGuiSdl3, canvas::CanvasRenderer};
+use sdl3::{event::Event, pixels::Color};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // use SDL's software renderer, so that no GPU driver is needed
+    sdl3::hint::set("SDL_RENDER_DRIVER", "software");
+
+    // initialize SDL and its video subsystem
+    let mut sdl = sdl3::init()?;
+    let video_subsystem = sdl.video()?;
+
+    // create a new window and its canvas
+    let window = video_subsystem
+        .window("Hello imgui-rs!", 1280, 720)
+        .position_centered()
+        .resizable()
+        .build()?;
+    let mut canvas = window.into_canvas();
+    let texture_creator = canvas.texture_creator();
+
+    // create imgui context and pair the platform integration with the canvas renderer
+    let mut imgui = ImGuiSdl3::builder()
+        .with_ini_filename(None)
+        .with_log_filename(None)
+        .with_font(&[imgui::FontSource::DefaultFontData { config: None }])
+        .with_clipboard(video_subsystem.clipboard())
+        .build_with_renderer(|imgui_context| CanvasRenderer::new(&texture_creator, imgui_context))?;
+
+    // start main loop
+    let mut event_pump = sdl.event_pump()?;
+
+    'main: loop {
+        for event in event_pump.poll_iter() {
+            // pass all events to imgui platform
+            imgui.handle_event(&event);
+
+            if let Event::Quit { .. } = event {
+                break 'main;
+            }
+        }
+
+        // create imgui UI here
+        let ui = imgui.begin_frame(&mut sdl, canvas.window(), &event_pump);
+        ui.show_demo_window(&mut true);
+
+        canvas.set_draw_color(Color::RGB(128, 128, 128));
+        canvas.clear();
+        imgui.end_frame(&mut canvas)?;
+        canvas.present();
+    }
+
+    Ok(())
+}
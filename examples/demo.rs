@@ -27,6 +27,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             .add_font(&[imgui::FontSource::DefaultFontData { config: None }]);
     });
 
+    // query the present modes and compositions supported by the window
+    let mut swapchain_settings = imgui.swapchain_settings(&device, &window);
+
     // start main loop
     let mut event_pump = sdl.event_pump()?;
 
@@ -59,10 +62,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 |ui| {
                     // create imgui UI here
                     ui.show_demo_window(&mut true);
+
+                    ui.window("Graphics").build(|| {
+                        swapchain_settings.ui(ui);
+                    });
                 },
-            );
+            )?;
 
             command_buffer.submit()?;
+
+            // swapchain parameters can only be changed outside of a frame
+            if swapchain_settings.is_dirty() {
+                imgui.apply_swapchain_settings(&device, &window, &mut swapchain_settings)?;
+            }
         } else {
             println!("Swapchain unavailable, cancel work");
             command_buffer.cancel();
@@ -1,5 +1,4 @@
 use imgui_sdl3::ImGuiSdl3;
-use imgui_sdl3::utils::create_texture;
 use sdl3::{event::Event, gpu::*, pixels::Color};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -27,6 +26,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             .add_font(&[imgui::FontSource::DefaultFontData { config: None }]);
     });
 
+    // decode the PNG, upload it and register it to get a TextureId
+    let rust_logo_png = include_bytes!("./assets/rust-logo.png");
+    let rust_logo_tex = imgui.load_texture_from_bytes(&device, rust_logo_png, Filter::Linear)?;
+
+    // draw the image at its actual size, only the header is read
+    let (width, height) = image::ImageReader::new(std::io::Cursor::new(rust_logo_png))
+        .with_guessed_format()?
+        .into_dimensions()?;
+    let rust_logo_size = [width as f32, height as f32];
+
     // start main loop
     let mut event_pump = sdl.event_pump()?;
 
@@ -49,33 +58,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .with_store_op(StoreOp::STORE)
                 .with_clear_color(Color::RGB(128, 128, 128))];
 
-            // Load a PNG from disk
-            let img = image::load_from_memory(include_bytes!("./assets/rust-logo.png"))?.to_rgba8();
-            let (w, h) = (img.width() as u32, img.height() as u32);
-            let pixels = img.into_raw(); // RGBA8 pixel bytes
-
-            // Create a GPU Texture and a Sampler from the device
-            let copy_commands = device.acquire_command_buffer()?;
-            let copy_pass = device.begin_copy_pass(&copy_commands)?;
-
-            let texture = create_texture(&device, &copy_pass, &pixels, w, h)?;
-
-            device.end_copy_pass(copy_pass);
-            copy_commands.submit()?;
-
-            let sampler: sdl3::gpu::Sampler = device.create_sampler(
-                SamplerCreateInfo::new()
-                    .with_min_filter(Filter::Linear)
-                    .with_mag_filter(Filter::Linear)
-                    .with_mipmap_mode(SamplerMipmapMode::Linear)
-                    .with_address_mode_u(SamplerAddressMode::Repeat)
-                    .with_address_mode_v(SamplerAddressMode::Repeat)
-                    .with_address_mode_w(SamplerAddressMode::Repeat),
-            )?;
-
-            // Register the texture and get a TextureId
-            let rust_logo_tex = imgui.push_texture(texture, sampler);
-
             imgui.render(
                 &mut sdl,
                 &device,
@@ -84,9 +66,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 &mut command_buffer,
                 &color_targets,
                 |ui| {
-                    ui.image_button("##", rust_logo_tex, [w as f32, h as f32]);
+                    ui.image_button("##", rust_logo_tex, rust_logo_size);
                 },
-            );
+            )?;
 
             command_buffer.submit()?;
         } else {
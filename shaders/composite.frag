@@ -0,0 +1,30 @@
+#version 450
+#pragma shader_stage(fragment)
+#pragma optimize(on)
+
+layout(location = 0) out vec4 fColor;
+
+// Premultiplied linear BT.2020 colors, 1.0 being 10000 nits
+layout(set = 2, binding = 0) uniform sampler2D sLayer;
+
+vec3 linear_to_pq(vec3 y) {
+  const float m1 = 0.1593017578125;
+  const float m2 = 78.84375;
+  const float c1 = 0.8359375;
+  const float c2 = 18.8515625;
+  const float c3 = 18.6875;
+
+  y = pow(clamp(y, 0.0, 1.0), vec3(m1));
+  return pow((c1 + c2 * y) / (1.0 + c3 * y), vec3(m2));
+}
+
+void main() {
+  // The layer has the size of the target, no filtering is needed
+  vec4 color = texelFetch(sLayer, ivec2(gl_FragCoord.xy), 0);
+  if (color.a <= 0.0) {
+    discard;
+  }
+
+  // PQ encodes straight colors, the result is premultiplied again for blending
+  fColor = vec4(linear_to_pq(color.rgb / color.a) * color.a, color.a);
+}
@@ -0,0 +1,11 @@
+#version 450
+#pragma shader_stage(vertex)
+#pragma optimize(on)
+
+out gl_PerVertex { vec4 gl_Position; };
+
+// A single triangle covering the target, without vertex buffer
+void main() {
+  vec2 pos = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
+  gl_Position = vec4(pos * 2.0 - 1.0, 0.0, 1.0);
+}
@@ -2,11 +2,168 @@
 #pragma shader_stage(fragment)
 #pragma optimize(on)
 
+// Must match the `SwapchainComposition` values used by the renderer
+#define COLOR_SPACE_SDR 0u
+#define COLOR_SPACE_SDR_LINEAR 1u
+#define COLOR_SPACE_HDR_EXTENDED_LINEAR 2u
+// HDR10 targets are drawn through a linear layer, see `COLOR_SPACE_HDR10_LAYER` in the renderer
+#define COLOR_SPACE_HDR10_LAYER 4u
+
+// Must match the `OUTPUT_FLAG_*` constants in the renderer
+#define FLAG_LINEARIZE_ALPHA 1u       // set when sampling the font atlas
+#define FLAG_PREMULTIPLIED_TEXTURE 2u // the texture holds premultiplied colors
+#define FLAG_PREMULTIPLIED_OUTPUT 4u  // the pipeline blends premultiplied colors
+#define FLAG_LINEAR_TEXTURE 8u        // the sampler returns linear colors
+
+// Must match the `VIEW_FLAG_*` constants in the renderer
+#define VIEW_FLAG_MIP_LEVEL 1u
+#define VIEW_FLAG_LINEARIZE_DEPTH 2u
+
+// Must match `ToneMapping` in the renderer
+#define TONE_MAPPING_NONE 0u
+#define TONE_MAPPING_CLAMP 1u
+#define TONE_MAPPING_REINHARD 2u
+#define TONE_MAPPING_ACES 3u
+
 layout(location = 0) out vec4 fColor;
 
+#ifdef TEXTURE_ARRAY
+layout(set = 2, binding = 0) uniform sampler2DArray sTexture;
+#else
 layout(set = 2, binding = 0) uniform sampler2D sTexture;
+#endif
+
+layout(set = 3, binding = 0) uniform Output {
+  uint uColorSpace;
+  float uSdrWhiteLevel; // in nits
+  uint uFlags;
+};
+
+#ifdef IMAGE_VIEW
+layout(set = 3, binding = 1) uniform View {
+  uvec4 uSwizzle; // source channel of each output channel: r, g, b, a, 0 or 1
+  float uLayer;
+  float uMipLevel;
+  float uExposure; // in stops
+  uint uToneMapping;
+  vec2 uDepthRange; // near and far planes
+  uint uViewFlags;
+};
+#endif
 
 layout(location = 0) in vec2 UV;
 layout(location = 1) in vec4 Color;
 
-void main() { fColor = Color * texture(sTexture, UV.st); }
\ No newline at end of file
+vec3 srgb_to_linear(vec3 c) { return mix(c / 12.92, pow((c + 0.055) / 1.055, vec3(2.4)), step(0.04045, c)); }
+
+float srgb_to_linear(float c) { return c <= 0.04045 ? c / 12.92 : pow((c + 0.055) / 1.055, 2.4); }
+
+vec3 linear_to_srgb(vec3 c) { return mix(c * 12.92, 1.055 * pow(c, vec3(1.0 / 2.4)) - 0.055, step(0.0031308, c)); }
+
+vec3 bt709_to_bt2020(vec3 c) {
+  const mat3 m = mat3(0.6274, 0.0691, 0.0164, 0.3293, 0.9195, 0.0880, 0.0433, 0.0114, 0.8956);
+  return m * c;
+}
+
+vec4 encode_color(vec4 color, vec4 texel) {
+  bool linear_texture = (uFlags & FLAG_LINEAR_TEXTURE) != 0u;
+
+  if (uColorSpace == COLOR_SPACE_SDR) {
+    if (linear_texture) {
+      texel.rgb = linear_to_srgb(max(texel.rgb, 0.0));
+    }
+    return color * texel;
+  }
+
+  // ImGui colors and UNORM textures hold sRGB-encoded values, decode them
+  // so that blending happens in linear space
+  color.rgb = srgb_to_linear(color.rgb);
+  if (!linear_texture) {
+    texel.rgb = srgb_to_linear(texel.rgb);
+  }
+
+  // Anti-aliased coverage in the font atlas is tuned for gamma-space blending
+  if ((uFlags & FLAG_LINEARIZE_ALPHA) != 0u) {
+    texel.a = srgb_to_linear(texel.a);
+  }
+
+  color *= texel;
+
+  if (uColorSpace == COLOR_SPACE_HDR_EXTENDED_LINEAR) {
+    // scRGB: 1.0 is 80 nits
+    color.rgb *= uSdrWhiteLevel / 80.0;
+  } else if (uColorSpace == COLOR_SPACE_HDR10_LAYER) {
+    // Blended linearly in the layer, PQ-encoded when compositing it
+    color.rgb = bt709_to_bt2020(color.rgb) * uSdrWhiteLevel / 10000.0;
+  }
+
+  return color;
+}
+
+#ifdef IMAGE_VIEW
+vec3 tone_map(vec3 c) {
+  if (uToneMapping == TONE_MAPPING_REINHARD) {
+    return c / (1.0 + c);
+  } else if (uToneMapping == TONE_MAPPING_ACES) {
+    // Narkowicz's fit of the ACES filmic curve
+    return clamp((c * (2.51 * c + 0.03)) / (c * (2.43 * c + 0.59) + 0.14), 0.0, 1.0);
+  }
+  return clamp(c, 0.0, 1.0);
+}
+
+vec4 apply_view(vec4 texel) {
+  if ((uViewFlags & VIEW_FLAG_LINEARIZE_DEPTH) != 0u) {
+    float near = uDepthRange.x;
+    float far = uDepthRange.y;
+    float z = near * far / (far - texel.r * (far - near));
+    texel = vec4(vec3((z - near) / (far - near)), 1.0);
+  }
+
+  float channels[6] = float[6](texel.r, texel.g, texel.b, texel.a, 0.0, 1.0);
+  texel = vec4(channels[uSwizzle.r], channels[uSwizzle.g], channels[uSwizzle.b], channels[uSwizzle.a]);
+
+  // Float textures hold linear values, bring them back to the sRGB-encoded range
+  // the rest of the shader expects
+  float exposure = exp2(uExposure);
+  if (uToneMapping != TONE_MAPPING_NONE) {
+    texel.rgb = linear_to_srgb(tone_map(max(texel.rgb * exposure, 0.0)));
+  } else if ((uFlags & FLAG_LINEAR_TEXTURE) != 0u) {
+    texel.rgb *= exposure;
+  } else {
+    // Exposure scales light, apply it to linear values
+    texel.rgb = linear_to_srgb(srgb_to_linear(texel.rgb) * exposure);
+  }
+
+  return texel;
+}
+
+vec4 sample_texture() {
+#ifdef TEXTURE_ARRAY
+  vec3 coord = vec3(UV, uLayer);
+#else
+  vec2 coord = UV;
+#endif
+
+  if ((uViewFlags & VIEW_FLAG_MIP_LEVEL) != 0u) {
+    return apply_view(textureLod(sTexture, coord, uMipLevel));
+  }
+  return apply_view(texture(sTexture, coord));
+}
+#else
+vec4 sample_texture() { return texture(sTexture, UV.st); }
+#endif
+
+void main() {
+  vec4 texel = sample_texture();
+
+  // Work on straight colors, so that color space conversions see the actual color
+  if ((uFlags & FLAG_PREMULTIPLIED_TEXTURE) != 0u && texel.a > 0.0) {
+    texel.rgb /= texel.a;
+  }
+
+  fColor = encode_color(Color, texel);
+
+  if ((uFlags & FLAG_PREMULTIPLIED_OUTPUT) != 0u) {
+    fColor.rgb *= fColor.a;
+  }
+}
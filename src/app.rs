@@ -0,0 +1,297 @@
+use std::{
+    error::Error,
+    time::{Duration, Instant},
+};
+
+use sdl3::{
+    EventPump, Sdl,
+    event::Event,
+    gpu::{ColorTargetInfo, CommandBuffer, Device, LoadOp, ShaderFormat, StoreOp},
+    pixels::Color,
+    video::{Window, WindowFlags},
+};
+
+use crate::{ImGuiSdl3, builder::ImGuiSdl3Builder};
+
+/// How long the runner sleeps between event checks while the window is hidden.
+const HIDDEN_WINDOW_WAIT: Duration = Duration::from_millis(100);
+
+/// Window and device settings used by `run`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppConfig {
+    pub title: String,
+    /// Window size in screen coordinates.
+    pub width: u32,
+    pub height: u32,
+    pub resizable: bool,
+    /// Color the window is cleared to before the scene and the UI are drawn.
+    pub clear_color: Color,
+    /// Enables the GPU debug layers.
+    pub debug: bool,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self::new("imgui-sdl3")
+    }
+}
+
+impl AppConfig {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            width: 1280,
+            height: 720,
+            resizable: true,
+            clear_color: Color::RGB(128, 128, 128),
+            debug: cfg!(debug_assertions),
+        }
+    }
+
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    pub fn with_clear_color(mut self, clear_color: Color) -> Self {
+        self.clear_color = clear_color;
+        self
+    }
+
+    pub fn with_debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+        self
+    }
+}
+
+/// SDL state owned by the runner, handed to every `App` callback.
+pub struct AppContext {
+    // The device is dropped first, while the window it claimed still exists
+    device: Device,
+    window: Window,
+    sdl: Sdl,
+    clear_color: Color,
+    quit: bool,
+}
+
+impl AppContext {
+    pub fn sdl(&self) -> &Sdl {
+        &self.sdl
+    }
+
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    pub fn window(&self) -> &Window {
+        &self.window
+    }
+
+    pub fn window_mut(&mut self) -> &mut Window {
+        &mut self.window
+    }
+
+    pub fn clear_color(&self) -> Color {
+        self.clear_color
+    }
+
+    pub fn set_clear_color(&mut self, clear_color: Color) {
+        self.clear_color = clear_color;
+    }
+
+    /// Stops the runner once the current frame is done.
+    pub fn quit(&mut self) {
+        self.quit = true;
+    }
+}
+
+/// An application driven by `run`.
+///
+/// Only `ui` is required, every other callback has an empty default.
+///
+/// # Example
+/// ```rust,no_run
+/// use imgui_sdl3::app::{App, AppContext};
+///
+/// struct Hello;
+///
+/// impl App for Hello {
+///     fn ui(&mut self, _ctx: &mut AppContext, ui: &mut imgui::Ui) {
+///         ui.window("Hello").build(|| ui.text("Hello world!"));
+///     }
+/// }
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     imgui_sdl3::run(Hello)
+/// }
+/// ```
+pub trait App {
+    /// Returns the window and device settings, read once before the window is created.
+    fn config(&self) -> AppConfig {
+        AppConfig::default()
+    }
+
+    /// Customizes the ImGui setup (fonts, ini file, flags...).
+    fn configure<'a>(&self, builder: ImGuiSdl3Builder<'a>) -> ImGuiSdl3Builder<'a> {
+        builder
+    }
+
+    /// Called once the window, the device and ImGui are ready, e.g. to load textures.
+    fn init(&mut self, _ctx: &mut AppContext, _imgui: &mut ImGuiSdl3) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called for every SDL event, after ImGui handled it.
+    ///
+    /// The runner quits on `Event::Quit` after this returns, call `AppContext::quit` to quit on other events.
+    fn handle_event(&mut self, _ctx: &mut AppContext, _event: &Event) {}
+
+    /// Called once per frame before rendering, with the time elapsed since the previous frame.
+    fn update(&mut self, _ctx: &mut AppContext, _imgui: &mut ImGuiSdl3, _delta: Duration) {}
+
+    /// Records the scene drawn under the UI.
+    ///
+    /// `target` is the swapchain texture, set to be cleared to the clear color. Return
+    /// `true` if it was used in a render pass, so that the UI is drawn over the scene
+    /// instead of clearing it.
+    fn render_scene(
+        &mut self,
+        _ctx: &mut AppContext,
+        _command_buffer: &mut CommandBuffer,
+        _target: ColorTargetInfo,
+    ) -> bool {
+        false
+    }
+
+    /// Builds the UI of the frame.
+    fn ui(&mut self, ctx: &mut AppContext, ui: &mut imgui::Ui);
+}
+
+/// Creates a window, a GPU device and ImGui, then runs the frame loop of `app` until it quits.
+///
+/// The runner skips rendering while the window is minimized or occluded, and drops
+/// frames whose swapchain texture cannot be acquired.
+pub fn run<A: App>(mut app: A) -> Result<(), Box<dyn Error>> {
+    let config = app.config();
+
+    let sdl = sdl3::init()?;
+    let video_subsystem = sdl.video()?;
+
+    let mut window_builder = video_subsystem.window(&config.title, config.width, config.height);
+    window_builder.position_centered();
+    if config.resizable {
+        window_builder.resizable();
+    }
+    let window = window_builder.build()?;
+
+    let device = Device::new(ShaderFormat::SPIRV, config.debug)?.with_window(&window)?;
+
+    let mut imgui = app.configure(ImGuiSdl3Builder::new()).build(&device, &window)?;
+
+    let mut event_pump = sdl.event_pump()?;
+    let mut ctx = AppContext {
+        device,
+        window,
+        sdl,
+        clear_color: config.clear_color,
+        quit: false,
+    };
+
+    // Release the GPU resources of ImGui while the device is still alive, on errors too:
+    // `ctx` is declared after `imgui`, it would be dropped first
+    let result = app
+        .init(&mut ctx, &mut imgui)
+        .and_then(|()| run_loop(&mut app, &mut ctx, &mut imgui, &mut event_pump));
+    drop(imgui);
+    result
+}
+
+fn run_loop<A: App>(
+    app: &mut A,
+    ctx: &mut AppContext,
+    imgui: &mut ImGuiSdl3,
+    event_pump: &mut EventPump,
+) -> Result<(), Box<dyn Error>> {
+    let mut last_frame = Instant::now();
+
+    while !ctx.quit {
+        // Sleeps until input arrives when idling is enabled and nothing changes
+        if let Some(event) = imgui.wait_event(event_pump) {
+            dispatch_event(app, ctx, imgui, &event);
+        }
+
+        for event in event_pump.poll_iter() {
+            dispatch_event(app, ctx, imgui, &event);
+        }
+
+        // Nothing is presented while the window is hidden, wait for it to come back
+        let flags = WindowFlags::from(ctx.window.window_flags());
+        if flags.intersects(WindowFlags::MINIMIZED | WindowFlags::OCCLUDED) {
+            if let Some(event) = event_pump.wait_event_timeout(HIDDEN_WINDOW_WAIT) {
+                dispatch_event(app, ctx, imgui, &event);
+            }
+            last_frame = Instant::now();
+            continue;
+        }
+
+        let now = Instant::now();
+        app.update(ctx, imgui, now.duration_since(last_frame));
+        last_frame = now;
+
+        if !ctx.quit {
+            render_frame(app, ctx, imgui, event_pump)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn dispatch_event<A: App>(app: &mut A, ctx: &mut AppContext, imgui: &mut ImGuiSdl3, event: &Event) {
+    imgui.handle_event(event);
+    app.handle_event(ctx, event);
+
+    if let Event::Quit { .. } = event {
+        ctx.quit = true;
+    }
+}
+
+fn render_frame<A: App>(
+    app: &mut A,
+    ctx: &mut AppContext,
+    imgui: &mut ImGuiSdl3,
+    event_pump: &EventPump,
+) -> Result<(), Box<dyn Error>> {
+    let mut command_buffer = ctx.device.acquire_command_buffer()?;
+
+    let Ok(swapchain) = command_buffer.wait_and_acquire_swapchain_texture(&ctx.window) else {
+        // The swapchain is unavailable (e.g. while resizing), drop this frame
+        command_buffer.cancel();
+        return Ok(());
+    };
+
+    let scene_target = ColorTargetInfo::default()
+        .with_texture(&swapchain)
+        .with_load_op(LoadOp::CLEAR)
+        .with_store_op(StoreOp::STORE)
+        .with_clear_color(ctx.clear_color);
+    let mut ui_target = ColorTargetInfo::default()
+        .with_texture(&swapchain)
+        .with_store_op(StoreOp::STORE)
+        .with_clear_color(ctx.clear_color);
+
+    let drew_scene = app.render_scene(ctx, &mut command_buffer, scene_target);
+    ui_target = ui_target.with_load_op(if drew_scene { LoadOp::LOAD } else { LoadOp::CLEAR });
+
+    let ui = imgui.new_frame(&mut ctx.sdl, &ctx.device, &ctx.window, event_pump)?;
+    app.ui(ctx, ui);
+    imgui.render_draw_data(&ctx.device, &mut command_buffer, &[ui_target]);
+
+    command_buffer.submit()?;
+    Ok(())
+}
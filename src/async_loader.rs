@@ -0,0 +1,342 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    panic::AssertUnwindSafe,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+    },
+    thread::{self, JoinHandle},
+};
+
+use imgui::TextureId;
+use sdl3::gpu::{CommandBuffer, Device, Sampler, Texture};
+
+use crate::{
+    renderer::{Renderer, TextureOptions},
+    utils::{self, DecodedImage, PixelFormat, TextureDescriptor},
+};
+
+/// Result of a decode job, the error is only reported through `LoadState::Failed`.
+pub type DecodeResult = Result<DecodedImage, Box<dyn Error + Send + Sync>>;
+
+type DecodeJob = Box<dyn FnOnce() -> DecodeResult + Send>;
+
+/// Color drawn while a texture is loading: a neutral gray at half opacity.
+const PLACEHOLDER_COLOR: [u8; 4] = [128, 128, 128, 128];
+
+/// Progress of a texture started with `AsyncTextureLoader::load`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadState {
+    /// The image is being decoded, or waits for its upload: the placeholder is drawn.
+    Loading,
+    /// The texture has been uploaded and is drawn in place of the placeholder.
+    Ready,
+    /// Decoding or uploading failed, the placeholder stays in place.
+    Failed(String),
+}
+
+struct Request {
+    id: TextureId,
+    /// Identifies the load, since ids are reused once unregistered.
+    serial: u64,
+    cancelled: Arc<AtomicBool>,
+    decode: DecodeJob,
+}
+
+struct Completed {
+    id: TextureId,
+    serial: u64,
+    result: DecodeResult,
+}
+
+/// A load waiting for its decoded pixels.
+struct Pending {
+    serial: u64,
+    cancelled: Arc<AtomicBool>,
+    sampler: Sampler,
+    generate_mipmaps: bool,
+}
+
+/// Decodes images on worker threads and uploads them to registered textures.
+///
+/// `load` returns a `TextureId` right away, which draws a placeholder until the
+/// decoded pixels are uploaded by `upload`. Uploads completed since the previous
+/// call are batched into a single copy pass, recorded into the frame's command buffer.
+///
+/// # Example
+/// ```rust,no_run
+/// # let device: sdl3::gpu::Device = unimplemented!();
+/// # let mut renderer: imgui_sdl3::renderer::Renderer = unimplemented!();
+/// # let mut command_buffer: sdl3::gpu::CommandBuffer = unimplemented!();
+/// # fn decode_thumbnail() -> imgui_sdl3::async_loader::DecodeResult { unimplemented!() }
+/// use imgui_sdl3::async_loader::{AsyncTextureLoader, LoadState};
+///
+/// let mut loader = AsyncTextureLoader::new(&device).unwrap();
+/// let sampler = renderer.default_sampler();
+/// let id = loader.load(&mut renderer, sampler, false, decode_thumbnail);
+///
+/// // every frame, before the render pass
+/// loader.upload(&device, &mut command_buffer, &mut renderer).unwrap();
+///
+/// // the thumbnail scrolled out of view
+/// if loader.state(id) == LoadState::Loading {
+///     loader.cancel(&mut renderer, id);
+/// }
+/// ```
+pub struct AsyncTextureLoader {
+    requests: Option<Sender<Request>>,
+    completed: Receiver<Completed>,
+    workers: Vec<JoinHandle<()>>,
+    placeholder: Texture<'static>,
+    pending: HashMap<TextureId, Pending>,
+    failed: HashMap<TextureId, String>,
+    /// Serial of the next load.
+    next_serial: u64,
+}
+
+impl AsyncTextureLoader {
+    /// Starts one worker thread per available CPU core.
+    pub fn new(device: &Device) -> Result<Self, Box<dyn Error>> {
+        let workers = thread::available_parallelism().map_or(1, |count| count.get());
+        Self::with_workers(device, workers)
+    }
+
+    /// Starts a given number of worker threads (at least one).
+    pub fn with_workers(device: &Device, workers: usize) -> Result<Self, Box<dyn Error>> {
+        let command_buffer = device.acquire_command_buffer()?;
+        let placeholder = utils::create_texture_with_format(
+            device,
+            &command_buffer,
+            &TextureDescriptor::new(1, 1, PixelFormat::Rgba8),
+            &PLACEHOLDER_COLOR,
+        )?;
+        command_buffer.submit()?;
+
+        let (request_sender, request_receiver) = mpsc::channel::<Request>();
+        let (completed_sender, completed) = mpsc::channel();
+        let request_receiver = Arc::new(Mutex::new(request_receiver));
+
+        let workers = (0..workers.max(1))
+            .map(|index| {
+                let requests = Arc::clone(&request_receiver);
+                let completed = completed_sender.clone();
+
+                thread::Builder::new()
+                    .name(format!("imgui-sdl3-loader-{index}"))
+                    .spawn(move || worker(&requests, &completed))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            requests: Some(request_sender),
+            completed,
+            workers,
+            placeholder,
+            pending: HashMap::new(),
+            failed: HashMap::new(),
+            next_serial: 0,
+        })
+    }
+
+    /// Queues a decode job and registers a placeholder texture for it.
+    ///
+    /// The returned `TextureId` stays valid once the texture is loaded, until it is
+    /// unregistered from the renderer. `generate_mipmaps` is applied on upload.
+    pub fn load<F>(&mut self, renderer: &mut Renderer, sampler: Sampler, generate_mipmaps: bool, decode: F) -> TextureId
+    where
+        F: FnOnce() -> DecodeResult + Send + 'static,
+    {
+        let id = renderer.register_texture_with_options(
+            self.placeholder.clone(),
+            sampler.clone(),
+            TextureOptions::default(),
+        );
+
+        // The slot may have belonged to a load that failed and was unregistered since
+        self.failed.remove(&id);
+
+        let serial = self.next_serial;
+        self.next_serial += 1;
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.pending.insert(
+            id,
+            Pending {
+                serial,
+                cancelled: Arc::clone(&cancelled),
+                sampler,
+                generate_mipmaps,
+            },
+        );
+
+        let request = Request {
+            id,
+            serial,
+            cancelled,
+            decode: Box::new(decode),
+        };
+        if self
+            .requests
+            .as_ref()
+            .is_none_or(|requests| requests.send(request).is_err())
+        {
+            self.pending.remove(&id);
+            self.failed.insert(id, "no worker thread is running".into());
+        }
+
+        id
+    }
+
+    /// Returns the progress of a load.
+    ///
+    /// Ids the loader does not know about (including loads that completed) are `Ready`.
+    pub fn state(&self, id: TextureId) -> LoadState {
+        if self.pending.contains_key(&id) {
+            LoadState::Loading
+        } else if let Some(error) = self.failed.get(&id) {
+            LoadState::Failed(error.clone())
+        } else {
+            LoadState::Ready
+        }
+    }
+
+    /// Returns the number of loads that have not completed yet.
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Cancels a pending or failed load, and releases its `TextureId`.
+    ///
+    /// Jobs that have not started are skipped, and the result of running jobs is
+    /// dropped. Returns `false` if the id is not a pending or failed load, textures that
+    /// finished loading are released with `Renderer::unregister_texture`.
+    pub fn cancel(&mut self, renderer: &mut Renderer, id: TextureId) -> bool {
+        if !self.forget(id) {
+            return false;
+        }
+
+        renderer.unregister_texture(id);
+        true
+    }
+
+    /// Uploads the images decoded since the previous call and swaps them into their slots.
+    ///
+    /// All uploads are recorded into a single copy pass of `command_buffer`, which must not
+    /// have a pass in progress: call this before beginning the frame's render pass.
+    /// Returns the number of textures that became ready.
+    pub fn upload(
+        &mut self,
+        device: &Device,
+        command_buffer: &mut CommandBuffer,
+        renderer: &mut Renderer,
+    ) -> Result<usize, Box<dyn Error>> {
+        let mut decoded = Vec::new();
+        for Completed { id, serial, result } in self.completed.try_iter() {
+            // Loads cancelled while their job was running, whose id may have been reused since
+            if self.pending.get(&id).is_none_or(|pending| pending.serial != serial) {
+                continue;
+            }
+            let pending = self.pending.remove(&id).unwrap();
+
+            match result {
+                Ok(image) => decoded.push((id, pending, image)),
+                Err(error) => {
+                    self.failed.insert(id, error.to_string());
+                }
+            }
+        }
+
+        if decoded.is_empty() {
+            return Ok(0);
+        }
+
+        let mut uploaded = Vec::with_capacity(decoded.len());
+        let copy_pass = device.begin_copy_pass(command_buffer)?;
+        for (id, pending, image) in decoded {
+            let descriptor = image.descriptor.with_mipmaps(pending.generate_mipmaps);
+            match utils::upload_texture(device, &copy_pass, &descriptor, &image.data) {
+                Ok(texture) => uploaded.push((id, pending.sampler, descriptor, texture)),
+                Err(error) => {
+                    self.failed.insert(id, error.to_string());
+                }
+            }
+        }
+        device.end_copy_pass(copy_pass);
+
+        let count = uploaded.len();
+        for (id, sampler, descriptor, texture) in uploaded {
+            if descriptor.generate_mipmaps {
+                utils::generate_mipmaps(command_buffer, &texture);
+            }
+
+            let options = TextureOptions::for_format(descriptor.format);
+            renderer.replace_texture(id, texture, sampler, options);
+        }
+
+        Ok(count)
+    }
+}
+
+impl AsyncTextureLoader {
+    /// Stops tracking a load, returns `false` if it was neither pending nor failed.
+    pub(crate) fn forget(&mut self, id: TextureId) -> bool {
+        if let Some(pending) = self.pending.remove(&id) {
+            pending.cancelled.store(true, Ordering::Relaxed);
+            return true;
+        }
+
+        self.failed.remove(&id).is_some()
+    }
+}
+
+impl Drop for AsyncTextureLoader {
+    fn drop(&mut self) {
+        for pending in self.pending.values() {
+            pending.cancelled.store(true, Ordering::Relaxed);
+        }
+
+        // Closing the channel lets the workers exit once their current job is done
+        self.requests = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn worker(requests: &Mutex<Receiver<Request>>, completed: &Sender<Completed>) {
+    loop {
+        // The lock is released as soon as a request is received
+        let request = match requests.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_) => return,
+        };
+        let Ok(Request {
+            id,
+            serial,
+            cancelled,
+            decode,
+        }) = request
+        else {
+            return;
+        };
+
+        if cancelled.load(Ordering::Relaxed) {
+            continue;
+        }
+
+        // A panicking decoder only fails its own load
+        let result =
+            std::panic::catch_unwind(AssertUnwindSafe(decode)).unwrap_or_else(|_| Err("the decoder panicked".into()));
+
+        if cancelled.load(Ordering::Relaxed) {
+            continue;
+        }
+
+        // `cancel` may still race with this check, `upload` drops results by serial
+        if completed.send(Completed { id, serial, result }).is_err() {
+            return;
+        }
+    }
+}
@@ -0,0 +1,60 @@
+// imgui internals used by `automation::UiDriver` to locate items, which have no C binding.
+//
+// An item is located by making it the navigation target of its window: imgui then stores its
+// rect while the item is submitted, without any change to the UI code.
+
+#include "imgui.h"
+#include "imgui_internal.h"
+
+extern "C" {
+
+// Hashes an ID stack entry like `ImGui::GetID`, `seed` being the entry below it.
+ImGuiID imgui_sdl3_hash_str(const char* str, ImGuiID seed)
+{
+    return ImHashStr(str, 0, seed);
+}
+
+// Focuses the window named `window_name` and makes the item `id` its navigation target.
+// Returns false if the window does not exist.
+bool imgui_sdl3_request_item(const char* window_name, ImGuiID id)
+{
+    ImGuiWindow* window = ImGui::FindWindowByName(window_name);
+    if (window == NULL)
+        return false;
+
+    ImGui::FocusWindow(window);
+    ImGui::SetNavID(id, ImGuiNavLayer_Main, 0, ImRect());
+    return true;
+}
+
+// State of the item requested with `imgui_sdl3_request_item`, as of the last frame.
+struct ImGuiSdl3ItemState
+{
+    float rect_min[2];
+    float rect_max[2];
+    bool visible;
+    bool hovered;
+    bool active;
+};
+
+// Returns false if the requested item `id` was not submitted during the last frame.
+bool imgui_sdl3_requested_item(ImGuiID id, ImGuiSdl3ItemState* state)
+{
+    ImGuiContext& g = *GImGui;
+    if (g.NavId != id || !g.NavIdIsAlive || g.NavWindow == NULL)
+        return false;
+
+    ImGuiWindow* window = g.NavWindow;
+    ImRect rect = ImGui::WindowRectRelToAbs(window, window->NavRectRel[g.NavLayer]);
+    state->rect_min[0] = rect.Min.x;
+    state->rect_min[1] = rect.Min.y;
+    state->rect_max[0] = rect.Max.x;
+    state->rect_max[1] = rect.Max.y;
+    ImRect clip_rect = g.NavLayer == ImGuiNavLayer_Main ? window->InnerClipRect : window->OuterRectClipped;
+    state->visible = !window->Hidden && rect.Overlaps(clip_rect);
+    state->hovered = g.HoveredId == id;
+    state->active = g.ActiveId == id;
+    return true;
+}
+
+}
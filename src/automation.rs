@@ -0,0 +1,415 @@
+//! Scripted UI automation, for end-to-end tests of UIs built on `ImGuiSdl3`.
+//!
+//! A `UiDriver` runs frames of the UI, feeding inputs through `Platform::replay_frame`
+//! instead of live SDL events: it moves the virtual mouse to items, clicks, drags, types
+//! text and presses shortcuts. The UI code is left as is.
+//!
+//! Items are located by their ID path: the name of their window followed by their ID
+//! stack, e.g. `"Settings/Apply"` for a button labeled `Apply` in the window `Settings`, or
+//! `"Settings/Tree/Apply"` inside `ui.tree_node("Tree")`. Labels are hashed like imgui
+//! does, so `"##"` and `"###"` suffixes follow imgui rules. Items of child windows are
+//! found under the name of the child window.
+//!
+//! Paired with `software::SoftwareRenderer`, no window nor GPU is needed.
+//!
+//! # Example
+//! ```rust,no_run
+//! use imgui_sdl3::{ImGuiSdl3, software::SoftwareRenderer};
+//!
+//! let mut imgui_context = imgui::Context::create();
+//! imgui_context.set_ini_filename(None);
+//! let renderer = SoftwareRenderer::new(&mut imgui_context);
+//! let mut imgui = ImGuiSdl3::with_renderer(imgui_context, renderer);
+//!
+//! let mut clicks = 0;
+//! let mut driver = imgui.automate(|ui| {
+//!     ui.window("Settings").build(|| {
+//!         if ui.button("Apply") {
+//!             clicks += 1;
+//!         }
+//!     });
+//! });
+//!
+//! driver.click("Settings/Apply").unwrap();
+//! drop(driver);
+//! assert_eq!(clicks, 1);
+//! ```
+
+use std::{
+    error::Error,
+    ffi::{CString, c_char},
+    fmt,
+    time::Duration,
+};
+
+use imgui::{Key, MouseButton, sys::ImGuiID};
+
+use crate::{
+    ImGuiSdl3,
+    backend::RendererBackend,
+    recording::{FrameInput, InputEvent},
+};
+
+/// Time step of the frames run by `UiDriver`.
+const FRAME_DELTA: Duration = Duration::from_micros(16_667);
+
+/// Frames run while waiting for an item to show up, e.g. a window appearing.
+const MAX_FRAMES_TO_FIND: u32 = 3;
+
+/// Frames a drag is spread over, so that imgui sees the mouse moving.
+const DRAG_STEPS: u32 = 4;
+
+/// State of an item in the last frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ItemInfo {
+    /// imgui ID of the item.
+    pub id: u32,
+    /// Upper-left corner, in logical pixels.
+    pub rect_min: [f32; 2],
+    /// Lower-right corner, in logical pixels.
+    pub rect_max: [f32; 2],
+    /// Whether the item is in the visible part of its window.
+    pub visible: bool,
+    pub hovered: bool,
+    /// Held (e.g. a pressed button or a dragged slider) or being edited.
+    pub active: bool,
+}
+
+impl ItemInfo {
+    pub fn center(&self) -> [f32; 2] {
+        [
+            (self.rect_min[0] + self.rect_max[0]) * 0.5,
+            (self.rect_min[1] + self.rect_max[1]) * 0.5,
+        ]
+    }
+}
+
+/// Error returned when an item cannot be located.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AutomationError {
+    /// No window has this path, or the item was not submitted.
+    NotFound(String),
+}
+
+impl fmt::Display for AutomationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AutomationError::NotFound(path) => write!(f, "no item found at {path:?}"),
+        }
+    }
+}
+
+impl Error for AutomationError {}
+
+/// Layout of `ImGuiSdl3ItemState` in `automation.cpp`.
+#[repr(C)]
+#[derive(Default)]
+struct ItemState {
+    rect_min: [f32; 2],
+    rect_max: [f32; 2],
+    visible: bool,
+    hovered: bool,
+    active: bool,
+}
+
+// Implemented in `automation.cpp`, with imgui internals
+unsafe extern "C" {
+    fn imgui_sdl3_hash_str(str: *const c_char, seed: ImGuiID) -> ImGuiID;
+    fn imgui_sdl3_request_item(window_name: *const c_char, id: ImGuiID) -> bool;
+    fn imgui_sdl3_requested_item(id: ImGuiID, state: *mut ItemState) -> bool;
+}
+
+/// Hashes an ID stack entry like `ImGui::GetID`, `seed` being the entry below it.
+fn hash_str(str: &str, seed: ImGuiID) -> ImGuiID {
+    // Labels with a nul byte cannot be submitted to imgui either
+    let str = CString::new(str).unwrap_or_default();
+    unsafe { imgui_sdl3_hash_str(str.as_ptr(), seed) }
+}
+
+/// Makes the item at `path` the navigation target of its window, so that imgui stores its
+/// rect on the next frame. Returns its ID, `None` if no window matches.
+///
+/// Window names may contain `/` (e.g. child windows), the longest existing one is used.
+fn request_item(path: &str) -> Option<ImGuiID> {
+    path.rmatch_indices('/').find_map(|(split, _)| {
+        let (window_name, item) = (&path[..split], &path[split + 1..]);
+        let window_id = hash_str(window_name, 0);
+        let id = item.split('/').fold(window_id, |seed, label| hash_str(label, seed));
+
+        let window_name = CString::new(window_name).ok()?;
+        unsafe { imgui_sdl3_request_item(window_name.as_ptr(), id) }.then_some(id)
+    })
+}
+
+/// State of the item requested with `request_item`, `None` if it was not submitted during the last frame.
+fn requested_item(id: ImGuiID) -> Option<ItemInfo> {
+    let mut state = ItemState::default();
+    unsafe { imgui_sdl3_requested_item(id, &mut state) }.then_some(ItemInfo {
+        id,
+        rect_min: state.rect_min,
+        rect_max: state.rect_max,
+        visible: state.visible,
+        hovered: state.hovered,
+        active: state.active,
+    })
+}
+
+/// Runs frames of a UI with scripted inputs, see the `automation` module.
+///
+/// Frames are finished without being rendered. Every action runs the frames it needs,
+/// so that the UI has reacted once it returns.
+pub struct UiDriver<'a, R: RendererBackend, F: FnMut(&mut imgui::Ui)> {
+    imgui: &'a mut ImGuiSdl3<R>,
+    ui: F,
+    /// Inputs fed to the next frame.
+    events: Vec<InputEvent>,
+    display_size: [f32; 2],
+    mouse_pos: [f32; 2],
+}
+
+impl<'a, R: RendererBackend, F: FnMut(&mut imgui::Ui)> UiDriver<'a, R, F> {
+    pub(crate) fn new(imgui: &'a mut ImGuiSdl3<R>, ui: F) -> Self {
+        let display_size = match imgui.context().io().display_size {
+            [width, height] if width > 0.0 && height > 0.0 => [width, height],
+            _ => [1280.0, 720.0],
+        };
+
+        Self {
+            imgui,
+            ui,
+            events: Vec::new(),
+            display_size,
+            mouse_pos: [-f32::MAX, -f32::MAX],
+        }
+    }
+
+    /// Sets the window size the frames are run with, in logical pixels.
+    pub fn with_display_size(mut self, width: f32, height: f32) -> Self {
+        self.display_size = [width, height];
+        self
+    }
+
+    /// Runs one frame, with the inputs sent since the previous one.
+    pub fn frame(&mut self) {
+        let frame = FrameInput {
+            events: std::mem::take(&mut self.events),
+            display_size: self.display_size,
+            framebuffer_scale: [1.0, 1.0],
+            content_scale: 1.0,
+            delta_time: FRAME_DELTA,
+            mouse_pos: self.mouse_pos,
+        };
+
+        (self.ui)(self.imgui.replay_frame(&frame));
+        self.imgui.context_mut().render();
+    }
+
+    pub fn frames(&mut self, count: u32) {
+        for _ in 0..count {
+            self.frame();
+        }
+    }
+
+    /// Locates an item by its ID path, see the `automation` module.
+    ///
+    /// Frames are run until the item shows up, for a few frames at most.
+    pub fn find(&mut self, path: &str) -> Result<ItemInfo, AutomationError> {
+        for _ in 0..MAX_FRAMES_TO_FIND {
+            let id = request_item(path);
+            self.frame();
+            if let Some(info) = id.and_then(requested_item) {
+                return Ok(info);
+            }
+        }
+
+        Err(AutomationError::NotFound(path.to_string()))
+    }
+
+    pub fn mouse_pos(&self) -> [f32; 2] {
+        self.mouse_pos
+    }
+
+    /// Moves the virtual mouse to a position, in logical pixels.
+    pub fn move_mouse(&mut self, pos: [f32; 2]) {
+        self.mouse_pos = pos;
+        self.frame();
+    }
+
+    /// Moves the virtual mouse over the center of an item.
+    pub fn hover(&mut self, path: &str) -> Result<ItemInfo, AutomationError> {
+        let info = self.find(path)?;
+        self.move_mouse(info.center());
+        self.find(path)
+    }
+
+    /// Presses and releases a mouse button where the mouse is.
+    pub fn click_button(&mut self, button: MouseButton) {
+        self.send(InputEvent::MouseButton(button, true));
+        self.frame();
+        self.send(InputEvent::MouseButton(button, false));
+        self.frame();
+    }
+
+    /// Left-clicks an item.
+    pub fn click(&mut self, path: &str) -> Result<ItemInfo, AutomationError> {
+        self.hover(path)?;
+        self.click_button(MouseButton::Left);
+        self.find(path)
+    }
+
+    /// Left-clicks an item twice.
+    pub fn double_click(&mut self, path: &str) -> Result<ItemInfo, AutomationError> {
+        self.hover(path)?;
+        self.click_button(MouseButton::Left);
+        self.click_button(MouseButton::Left);
+        self.find(path)
+    }
+
+    /// Drags an item with the left mouse button, by `delta` logical pixels.
+    pub fn drag(&mut self, path: &str, delta: [f32; 2]) -> Result<ItemInfo, AutomationError> {
+        let start = self.hover(path)?.center();
+
+        self.send(InputEvent::MouseButton(MouseButton::Left, true));
+        self.frame();
+        for step in 1..=DRAG_STEPS {
+            let t = step as f32 / DRAG_STEPS as f32;
+            self.move_mouse([start[0] + delta[0] * t, start[1] + delta[1] * t]);
+        }
+        self.send(InputEvent::MouseButton(MouseButton::Left, false));
+        self.frame();
+
+        self.find(path)
+    }
+
+    /// Types text into the focused item, e.g. after clicking a text field.
+    pub fn type_text(&mut self, text: &str) {
+        self.send(InputEvent::Text(text.to_string()));
+        self.frame();
+    }
+
+    /// Presses and releases a key.
+    pub fn press_key(&mut self, key: Key) {
+        self.shortcut(&[], key);
+    }
+
+    /// Presses a key while holding modifiers, e.g. `shortcut(&[Key::ModCtrl], Key::S)`.
+    pub fn shortcut(&mut self, modifiers: &[Key], key: Key) {
+        for modifier in modifiers {
+            self.send(InputEvent::Key(*modifier, true));
+        }
+        self.send(InputEvent::Key(key, true));
+        self.frame();
+
+        self.send(InputEvent::Key(key, false));
+        for modifier in modifiers {
+            self.send(InputEvent::Key(*modifier, false));
+        }
+        self.frame();
+    }
+
+    /// Queues an input for the next frame.
+    pub fn send(&mut self, event: InputEvent) {
+        self.events.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{software::SoftwareRenderer, test_utils::imgui_lock};
+
+    fn imgui() -> ImGuiSdl3<SoftwareRenderer> {
+        let mut imgui_context = imgui::Context::create();
+        imgui_context.set_ini_filename(None);
+        let renderer = SoftwareRenderer::new(&mut imgui_context);
+        ImGuiSdl3::with_renderer(imgui_context, renderer)
+    }
+
+    #[test]
+    fn finds_items_by_id_path() {
+        let _lock = imgui_lock();
+        let mut imgui = imgui();
+
+        let ids = [Cell::new(0), Cell::new(0), Cell::new(0), Cell::new(0)];
+        let item_id = || unsafe { imgui::sys::igGetItemID() };
+        let mut driver = imgui.automate(|ui| {
+            ui.window("A").position([0.0, 0.0], imgui::Condition::Always).build(|| {
+                ui.button("Ok");
+                ids[0].set(item_id());
+
+                let _row = ui.push_id("row");
+                ui.button("Delete");
+                ids[1].set(item_id());
+                ui.button("Save###save");
+                ids[2].set(item_id());
+            });
+            ui.window("B")
+                .position([200.0, 0.0], imgui::Condition::Always)
+                .build(|| {
+                    ui.button("Ok");
+                    ids[3].set(item_id());
+                });
+        });
+
+        for (path, id) in ["A/Ok", "A/row/Delete", "A/row/###save", "B/Ok"].into_iter().zip(&ids) {
+            let info = driver.find(path).unwrap();
+            assert_eq!(info.id, id.get(), "{path}");
+            assert!(info.visible && info.rect_min[0] < info.rect_max[0], "{path}");
+        }
+
+        for path in ["A/Cancel", "A/Delete", "C/Ok", "Ok"] {
+            assert_eq!(driver.find(path), Err(AutomationError::NotFound(path.to_string())));
+        }
+    }
+
+    #[test]
+    fn drives_widgets() {
+        let _lock = imgui_lock();
+        let mut imgui = imgui();
+
+        let mut clicks = 0;
+        let mut value = 0.0;
+        let mut name = String::new();
+        let mut saved = false;
+
+        let mut driver = imgui
+            .automate(|ui| {
+                ui.window("Settings")
+                    .position([0.0, 0.0], imgui::Condition::Always)
+                    .size([300.0, 200.0], imgui::Condition::Always)
+                    .build(|| {
+                        if ui.button("Apply") {
+                            clicks += 1;
+                        }
+                        ui.slider("Value", 0.0, 100.0, &mut value);
+                        ui.input_text("Name", &mut name).build();
+                    });
+
+                if ui.io().key_ctrl && ui.is_key_pressed(Key::S) {
+                    saved = true;
+                }
+            })
+            .with_display_size(400.0, 300.0);
+
+        let apply = driver.click("Settings/Apply").unwrap();
+        assert!(apply.visible && apply.hovered);
+
+        let slider = driver.drag("Settings/Value", [60.0, 0.0]).unwrap();
+        assert!(!slider.active);
+
+        let field = driver.click("Settings/Name").unwrap();
+        assert!(field.active);
+        driver.type_text("Ferris");
+        driver.press_key(Key::Enter);
+
+        driver.shortcut(&[Key::ModCtrl], Key::S);
+        drop(driver);
+
+        assert_eq!(clicks, 1);
+        assert!(value > 0.0 && value < 100.0, "slider value {value}");
+        assert_eq!(name, "Ferris");
+        assert!(saved);
+    }
+}
@@ -0,0 +1,48 @@
+use std::error::Error;
+
+use imgui::{DrawData, TextureId};
+
+/// A renderer `ImGuiSdl3` can draw with.
+///
+/// The SDL GPU `renderer::Renderer` implements it, as does `canvas::CanvasRenderer`
+/// with the `canvas` feature. Implement it to pair the SDL3 platform integration with
+/// another graphics API (e.g. wgpu), see `ImGuiSdl3::with_renderer`.
+///
+/// Creating the renderer itself is left to each backend, since they need different
+/// resources to start with. Renderers upload the font atlas of the context they are
+/// created with.
+pub trait RendererBackend {
+    /// Handle to the graphics device textures are created with.
+    type Device: ?Sized;
+    /// Texture registered for ImGui to draw, along with what is needed to sample it.
+    type Texture;
+    /// Where a frame is rendered, e.g. a command buffer and its color targets.
+    type Target<'a>;
+
+    /// Uploads the font atlas of `imgui_context` again and sets the renderer backend flags.
+    ///
+    /// Called when fonts change, see `ImGuiSdl3::reload_fonts`.
+    fn init(&mut self, device: &Self::Device, imgui_context: &mut imgui::Context) -> Result<(), Box<dyn Error>>;
+
+    /// Registers a texture until it is unregistered.
+    fn register_texture(&mut self, texture: Self::Texture) -> TextureId;
+
+    /// Registers a texture for the current frame only.
+    fn push_texture(&mut self, texture: Self::Texture) -> TextureId;
+
+    /// Releases a registered texture, returning it.
+    fn unregister_texture(&mut self, id: TextureId) -> Option<Self::Texture>;
+
+    /// Renders draw data finalized with `imgui::Context::render`.
+    fn render_draw_data(&mut self, target: Self::Target<'_>, draw_data: &DrawData) -> Result<(), Box<dyn Error>>;
+
+    /// Recreates the backend resources after the device was lost or reset.
+    ///
+    /// Textures registered by the application may have been lost with the device and
+    /// must then be registered again.
+    fn recreate_device_objects(
+        &mut self,
+        device: &Self::Device,
+        imgui_context: &mut imgui::Context,
+    ) -> Result<(), Box<dyn Error>>;
+}
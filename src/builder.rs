@@ -0,0 +1,307 @@
+use std::{error::Error, path::PathBuf};
+
+use imgui::{ConfigFlags, Context, FontSource};
+use sdl3::{
+    clipboard::ClipboardUtil,
+    gpu::{Device, Filter, SampleCount, SamplerAddressMode, TextureFormat},
+    video::Window,
+};
+
+use crate::{
+    ImGuiSdl3,
+    backend::RendererBackend,
+    clock::Clock,
+    idle::IdleConfig,
+    platform::{Platform, RelativeMouse, SdlClipboard},
+    renderer::{Renderer, RendererConfig, swapchain_color_space},
+};
+
+type ConfigureContext<'a> = Box<dyn FnOnce(&mut Context) + 'a>;
+
+/// Declarative configuration of an `ImGuiSdl3` instance.
+///
+/// Options left unset keep the imgui-rs and renderer defaults.
+///
+/// # Example
+/// ```rust,no_run
+/// # let device: sdl3::gpu::Device = unimplemented!();
+/// # let window: sdl3::video::Window = unimplemented!();
+/// # let video_subsystem: sdl3::VideoSubsystem = unimplemented!();
+/// use imgui_sdl3::builder::ImGuiSdl3Builder;
+///
+/// let imgui = ImGuiSdl3Builder::new()
+///     .with_ini_filename(None)
+///     .with_log_filename(None)
+///     .with_font(&[imgui::FontSource::DefaultFontData { config: None }])
+///     .with_keyboard_navigation(true)
+///     .with_clipboard(video_subsystem.clipboard())
+///     .with_texture_filter(sdl3::gpu::Filter::Nearest)
+///     .build(&device, &window)
+///     .unwrap();
+/// ```
+pub struct ImGuiSdl3Builder<'a> {
+    ini_filename: Option<Option<PathBuf>>,
+    log_filename: Option<Option<PathBuf>>,
+    fonts: Vec<Vec<FontSource<'a>>>,
+    config_flags: ConfigFlags,
+    mouse_cursor_change: bool,
+    software_cursor: bool,
+    relative_mouse: RelativeMouse,
+    clipboard: Option<ClipboardUtil>,
+    target_format: Option<TextureFormat>,
+    sample_count: SampleCount,
+    texture_filter: Filter,
+    texture_address_mode: SamplerAddressMode,
+    idle: IdleConfig,
+    clock: Option<Box<dyn Clock>>,
+    configure: Option<ConfigureContext<'a>>,
+}
+
+impl Default for ImGuiSdl3Builder<'_> {
+    fn default() -> Self {
+        Self {
+            ini_filename: None,
+            log_filename: None,
+            fonts: Vec::new(),
+            config_flags: ConfigFlags::empty(),
+            mouse_cursor_change: true,
+            software_cursor: false,
+            relative_mouse: RelativeMouse::Suspend,
+            clipboard: None,
+            target_format: None,
+            sample_count: SampleCount::NoMultiSampling,
+            texture_filter: Filter::Linear,
+            texture_address_mode: SamplerAddressMode::ClampToEdge,
+            idle: IdleConfig::default(),
+            clock: None,
+            configure: None,
+        }
+    }
+}
+
+impl<'a> ImGuiSdl3Builder<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the file the window layout is saved to, `None` disables saving.
+    pub fn with_ini_filename(mut self, ini_filename: impl Into<Option<PathBuf>>) -> Self {
+        self.ini_filename = Some(ini_filename.into());
+        self
+    }
+
+    /// Sets the file logs are written to, `None` disables logging to a file.
+    pub fn with_log_filename(mut self, log_filename: impl Into<Option<PathBuf>>) -> Self {
+        self.log_filename = Some(log_filename.into());
+        self
+    }
+
+    /// Adds a font, merged from one or more sources (e.g. a text font and an icon font).
+    ///
+    /// The first font added is the default one. Without any font, the default imgui font is used.
+    pub fn with_font(mut self, sources: &[FontSource<'a>]) -> Self {
+        self.fonts.push(sources.to_vec());
+        self
+    }
+
+    /// Enables additional imgui configuration flags.
+    pub fn with_config_flags(mut self, config_flags: ConfigFlags) -> Self {
+        self.config_flags.insert(config_flags);
+        self
+    }
+
+    /// Enables navigating the UI with the keyboard.
+    pub fn with_keyboard_navigation(mut self, enabled: bool) -> Self {
+        self.config_flags.set(ConfigFlags::NAV_ENABLE_KEYBOARD, enabled);
+        self
+    }
+
+    /// Enables docking windows into each other.
+    #[cfg(feature = "docking")]
+    pub fn with_docking(mut self, enabled: bool) -> Self {
+        self.config_flags.set(ConfigFlags::DOCKING_ENABLE, enabled);
+        self
+    }
+
+    /// Lets imgui change the shape of the system cursor (enabled by default).
+    pub fn with_mouse_cursor_change(mut self, enabled: bool) -> Self {
+        self.mouse_cursor_change = enabled;
+        self
+    }
+
+    /// Makes imgui draw the mouse cursor itself, instead of using the system cursor.
+    ///
+    /// Useful where the system cursor is not shown, e.g. exclusive fullscreen or screen captures.
+    pub fn with_software_cursor(mut self, enabled: bool) -> Self {
+        self.software_cursor = enabled;
+        self
+    }
+
+    /// Sets how imgui gets the mouse while the application uses relative mouse mode.
+    pub fn with_relative_mouse(mut self, relative_mouse: RelativeMouse) -> Self {
+        self.relative_mouse = relative_mouse;
+        self
+    }
+
+    /// Gives imgui access to the system clipboard, which it cannot use otherwise.
+    pub fn with_clipboard(mut self, clipboard: ClipboardUtil) -> Self {
+        self.clipboard = Some(clipboard);
+        self
+    }
+
+    /// Renders into targets of a fixed format instead of the window swapchain.
+    ///
+    /// The renderer then no longer follows changes of the swapchain format.
+    /// Colors are encoded for SDR targets, see `Renderer::set_color_space` for HDR targets.
+    pub fn with_target_format(mut self, target_format: TextureFormat) -> Self {
+        self.target_format = Some(target_format);
+        self
+    }
+
+    /// Renders into multisampled targets, e.g. in the render pass of a multisampled scene.
+    ///
+    /// Swapchain textures are single-sampled, this requires `with_target_format`.
+    /// ImGui geometry is anti-aliased on its own, multisampling only matches the targets.
+    pub fn with_sample_count(mut self, sample_count: SampleCount) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
+    /// Sets the filter of the default sampler, used for the font atlas.
+    ///
+    /// Registered and pushed textures bring their own sampler, `Renderer::default_sampler`
+    /// returns this one to share it.
+    pub fn with_texture_filter(mut self, filter: Filter) -> Self {
+        self.texture_filter = filter;
+        self
+    }
+
+    /// Sets the address mode of the default sampler.
+    pub fn with_texture_address_mode(mut self, address_mode: SamplerAddressMode) -> Self {
+        self.texture_address_mode = address_mode;
+        self
+    }
+
+    /// Sets the power-saving settings, see `ImGuiSdl3::wait_event`.
+    pub fn with_idle(mut self, idle: IdleConfig) -> Self {
+        self.idle = idle;
+        self
+    }
+
+    /// Sets the time source of the frame time step, e.g. a `clock::FixedClock` for reproducible frames.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Some(Box::new(clock));
+        self
+    }
+
+    /// Runs a closure on the imgui context for anything not covered by the builder.
+    ///
+    /// The closure runs after the other options are applied, before the font atlas is uploaded.
+    pub fn with_context<T>(mut self, configure: T) -> Self
+    where
+        T: FnOnce(&mut Context) + 'a,
+    {
+        self.configure = Some(Box::new(configure));
+        self
+    }
+
+    /// Creates the `ImGuiSdl3` instance.
+    pub fn build(mut self, device: &Device, window: &Window) -> Result<ImGuiSdl3, Box<dyn Error>> {
+        if self.target_format.is_none() && self.sample_count != SampleCount::NoMultiSampling {
+            return Err("multisampled targets require a target format, the swapchain is single-sampled".into());
+        }
+
+        let mut imgui_context = self.create_context();
+
+        // The color space is only deduced from the format for the window swapchain
+        let config = match self.target_format {
+            Some(target_format) => RendererConfig::new(target_format),
+            None => {
+                let format = device.get_swapchain_texture_format(window);
+                RendererConfig::new(format).with_color_space(swapchain_color_space(format))
+            }
+        }
+        .with_filter(self.texture_filter)
+        .with_address_mode(self.texture_address_mode)
+        .with_sample_count(self.sample_count);
+        let renderer = Renderer::with_config(device, &config, &mut imgui_context)?;
+
+        let follow_swapchain_format = self.target_format.is_none();
+        Ok(self.assemble(imgui_context, renderer, follow_swapchain_format))
+    }
+
+    /// Creates an `ImGuiSdl3` instance drawing with another renderer backend.
+    ///
+    /// `create_renderer` is called with the configured context, whose font atlas it uploads.
+    /// The target format and texture options only apply to the SDL GPU renderer.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use imgui_sdl3::{ImGuiSdl3, software::SoftwareRenderer};
+    ///
+    /// let imgui: ImGuiSdl3<SoftwareRenderer> = ImGuiSdl3::builder()
+    ///     .with_ini_filename(None)
+    ///     .build_with_renderer(|imgui_context| Ok(SoftwareRenderer::new(imgui_context)))
+    ///     .unwrap();
+    /// ```
+    pub fn build_with_renderer<R, F>(mut self, create_renderer: F) -> Result<ImGuiSdl3<R>, Box<dyn Error>>
+    where
+        R: RendererBackend,
+        F: FnOnce(&mut Context) -> Result<R, Box<dyn Error>>,
+    {
+        let mut imgui_context = self.create_context();
+        let renderer = create_renderer(&mut imgui_context)?;
+        Ok(self.assemble(imgui_context, renderer, false))
+    }
+}
+
+impl ImGuiSdl3Builder<'_> {
+    /// Creates the imgui context with the context options, before the renderer uploads its font atlas.
+    fn create_context(&mut self) -> Context {
+        let mut imgui_context = Context::create();
+
+        if let Some(ini_filename) = self.ini_filename.take() {
+            imgui_context.set_ini_filename(ini_filename);
+        }
+        if let Some(log_filename) = self.log_filename.take() {
+            imgui_context.set_log_filename(log_filename);
+        }
+
+        for sources in &self.fonts {
+            imgui_context.fonts().add_font(sources);
+        }
+
+        let io = imgui_context.io_mut();
+        io.config_flags.insert(self.config_flags);
+        io.config_flags
+            .set(ConfigFlags::NO_MOUSE_CURSOR_CHANGE, !self.mouse_cursor_change);
+        io.mouse_draw_cursor = self.software_cursor;
+
+        if let Some(clipboard) = self.clipboard.take() {
+            imgui_context.set_clipboard_backend(SdlClipboard::new(clipboard));
+        }
+
+        if let Some(configure) = self.configure.take() {
+            configure(&mut imgui_context);
+        }
+
+        imgui_context
+    }
+
+    /// Sets up the platform (clock, relative mouse, idling) and pairs it with the renderer.
+    ///
+    /// This is the path shared by every way of creating an `ImGuiSdl3`.
+    pub(crate) fn assemble<R: RendererBackend>(
+        self,
+        mut imgui_context: Context,
+        renderer: R,
+        follow_swapchain_format: bool,
+    ) -> ImGuiSdl3<R> {
+        let mut platform = Platform::new(&mut imgui_context).with_relative_mouse(self.relative_mouse);
+        if let Some(clock) = self.clock {
+            platform.set_clock(clock);
+        }
+
+        ImGuiSdl3::from_parts(imgui_context, platform, renderer, follow_swapchain_format, self.idle)
+    }
+}
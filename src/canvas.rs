@@ -0,0 +1,286 @@
+use std::{
+    error::Error,
+    mem::{offset_of, size_of},
+};
+
+use imgui::{BackendFlags, DrawCmdParams, DrawData, DrawIdx, DrawVert, TextureId, internal::RawWrapper};
+use sdl3::{
+    pixels::PixelFormat,
+    rect::Rect,
+    render::{BlendMode, Canvas, RenderTarget, Texture, TextureCreator, WindowCanvas},
+    sys::{pixels::SDL_FColor, render::SDL_RenderGeometryRaw},
+    video::WindowContext,
+};
+
+use crate::{backend::RendererBackend, coordinates::Coordinates};
+
+/// Renderer backend for imgui using `SDL_Renderer` geometry rendering.
+///
+/// Unlike `renderer::Renderer`, this backend does not need a GPU driver: it runs on
+/// every SDL render driver, including the `software` one (set the `SDL_RENDER_DRIVER`
+/// hint), which makes it suitable for VMs, remote desktops and CI machines.
+///
+/// Textures are borrowed from the `TextureCreator` the renderer was created with, of a
+/// window canvas by default. Only straight alpha is supported, and colors are blended in
+/// the space of the canvas.
+pub struct CanvasRenderer<'r, T = WindowContext> {
+    /// Creates the font atlas, again when fonts change or the render device is reset.
+    texture_creator: &'r TextureCreator<T>,
+    /// Registered textures indexed by `TextureId`, the font atlas is always 0.
+    textures: Vec<Option<Texture<'r>>>,
+    /// Textures pushed for the current frame only, released after rendering.
+    frame_textures: Vec<TextureId>,
+}
+
+impl<'r, T> CanvasRenderer<'r, T> {
+    /// Creates a new ImGui canvas renderer and uploads the ImGui font atlas.
+    pub fn new(
+        texture_creator: &'r TextureCreator<T>,
+        imgui_context: &mut imgui::Context,
+    ) -> Result<Self, Box<dyn Error>> {
+        // Draw commands are issued with a vertex offset
+        imgui_context
+            .io_mut()
+            .backend_flags
+            .insert(BackendFlags::RENDERER_HAS_VTX_OFFSET);
+
+        Ok(Self {
+            texture_creator,
+            textures: vec![Some(create_font_texture(texture_creator, imgui_context)?)],
+            frame_textures: Vec::new(),
+        })
+    }
+
+    /// Uploads the font atlas again, e.g. after fonts were added to the context.
+    pub fn reload_font_texture(&mut self, imgui_context: &mut imgui::Context) -> Result<(), Box<dyn Error>> {
+        self.textures[0] = Some(create_font_texture(self.texture_creator, imgui_context)?);
+        Ok(())
+    }
+
+    /// Registers a texture for the current frame.
+    pub fn push_texture(&mut self, texture: Texture<'r>) -> TextureId {
+        let id = self.register_texture(texture);
+        self.frame_textures.push(id);
+        id
+    }
+
+    /// Registers a texture until it is unregistered, so that its `TextureId` stays
+    /// valid across frames.
+    ///
+    /// The texture is switched to alpha blending.
+    pub fn register_texture(&mut self, mut texture: Texture<'r>) -> TextureId {
+        texture.set_blend_mode(BlendMode::Blend);
+        let texture = Some(texture);
+
+        // Reuse the first free slot, the font atlas slot is never freed
+        match self.textures.iter().position(Option::is_none) {
+            Some(index) => {
+                self.textures[index] = texture;
+                TextureId::from(index)
+            }
+            None => {
+                self.textures.push(texture);
+                TextureId::from(self.textures.len() - 1)
+            }
+        }
+    }
+
+    /// Releases a registered texture, returning it.
+    ///
+    /// The font atlas cannot be unregistered.
+    pub fn unregister_texture(&mut self, id: TextureId) -> Option<Texture<'r>> {
+        if id.id() == 0 {
+            return None;
+        }
+
+        // The slot may be reused before the end of the frame, which must not release it
+        self.frame_textures.retain(|&frame_id| frame_id != id);
+        self.textures.get_mut(id.id()).and_then(Option::take)
+    }
+
+    /// Returns a registered texture, e.g. to update its content.
+    pub fn texture_mut(&mut self, id: TextureId) -> Option<&mut Texture<'r>> {
+        self.textures.get_mut(id.id()).and_then(Option::as_mut)
+    }
+
+    /// Renders the current ImGui draw data into the canvas.
+    ///
+    /// The canvas is not cleared nor presented, and its clip rect is reset afterwards.
+    pub fn render<C: RenderTarget>(
+        &mut self,
+        canvas: &mut Canvas<C>,
+        imgui_context: &mut imgui::Context,
+    ) -> Result<(), Box<dyn Error>> {
+        let draw_data = imgui_context.render();
+        self.render_draw_data(canvas, draw_data)
+    }
+
+    /// Renders draw data already finalized with `imgui::Context::render`.
+    pub fn render_draw_data<C: RenderTarget>(
+        &mut self,
+        canvas: &mut Canvas<C>,
+        draw_data: &DrawData,
+    ) -> Result<(), Box<dyn Error>> {
+        let [width, height] = draw_data.display_size;
+        let coordinates = Coordinates::from_draw_data(draw_data);
+
+        // Skip rendering if there's nothing to draw
+        if width == 0.0 || height == 0.0 || draw_data.total_vtx_count == 0 {
+            self.release_frame_textures();
+            return Ok(());
+        }
+
+        let mut positions = Vec::new();
+        let mut colors = Vec::new();
+
+        for draw_list in draw_data.draw_lists() {
+            let vertices = draw_list.vtx_buffer();
+            let indices = draw_list.idx_buffer();
+
+            // SDL takes positions in pixels and colors as floats, UVs are used as is
+            positions.clear();
+            positions.extend(vertices.iter().map(|v| coordinates.display_to_pixels(v.pos)));
+            colors.clear();
+            colors.extend(vertices.iter().map(|v| SDL_FColor {
+                r: v.col[0] as f32 / 255.0,
+                g: v.col[1] as f32 / 255.0,
+                b: v.col[2] as f32 / 255.0,
+                a: v.col[3] as f32 / 255.0,
+            }));
+
+            for draw_cmd in draw_list.commands() {
+                match draw_cmd {
+                    imgui::DrawCmd::Elements {
+                        count,
+                        cmd_params:
+                            DrawCmdParams {
+                                clip_rect,
+                                idx_offset,
+                                vtx_offset,
+                                texture_id,
+                            },
+                    } => {
+                        // Calculate clip rectangle
+                        let [min_x, min_y, max_x, max_y] = coordinates.clip_rect_to_pixels(clip_rect);
+
+                        // Skip if the clip rectangle is empty
+                        if max_x > min_x && max_y > min_y {
+                            let (width, height) = ((max_x - min_x) as u32, (max_y - min_y) as u32);
+                            canvas.set_clip_rect(Rect::new(min_x, min_y, width, height));
+                        } else {
+                            continue;
+                        }
+
+                        // Skip draws referencing textures that were unregistered
+                        let Some(Some(texture)) = self.textures.get(texture_id.id()) else {
+                            continue;
+                        };
+
+                        let uvs = &vertices[vtx_offset..];
+                        let success = unsafe {
+                            SDL_RenderGeometryRaw(
+                                canvas.raw(),
+                                texture.raw(),
+                                positions[vtx_offset..].as_ptr().cast(),
+                                size_of::<[f32; 2]>() as i32,
+                                colors[vtx_offset..].as_ptr(),
+                                size_of::<SDL_FColor>() as i32,
+                                uvs.as_ptr().cast::<u8>().add(offset_of!(DrawVert, uv)).cast(),
+                                size_of::<DrawVert>() as i32,
+                                (vertices.len() - vtx_offset) as i32,
+                                indices[idx_offset..].as_ptr().cast(),
+                                count as i32,
+                                size_of::<DrawIdx>() as i32,
+                            )
+                        };
+                        if !success {
+                            return Err(sdl3::get_error().into());
+                        }
+                    }
+
+                    imgui::DrawCmd::RawCallback { callback, raw_cmd } => unsafe {
+                        callback(draw_list.raw(), raw_cmd);
+                    },
+
+                    _ => {}
+                }
+            }
+        }
+
+        canvas.set_clip_rect(None);
+
+        self.release_frame_textures();
+
+        Ok(())
+    }
+}
+
+impl<'r> RendererBackend for CanvasRenderer<'r> {
+    /// Textures are created with the texture creator the renderer was created with.
+    type Device = TextureCreator<WindowContext>;
+    type Texture = Texture<'r>;
+    type Target<'a> = &'a mut WindowCanvas;
+
+    fn init(&mut self, _device: &Self::Device, imgui_context: &mut imgui::Context) -> Result<(), Box<dyn Error>> {
+        imgui_context
+            .io_mut()
+            .backend_flags
+            .insert(BackendFlags::RENDERER_HAS_VTX_OFFSET);
+        self.reload_font_texture(imgui_context)
+    }
+
+    fn register_texture(&mut self, texture: Texture<'r>) -> TextureId {
+        CanvasRenderer::register_texture(self, texture)
+    }
+
+    fn push_texture(&mut self, texture: Texture<'r>) -> TextureId {
+        CanvasRenderer::push_texture(self, texture)
+    }
+
+    fn unregister_texture(&mut self, id: TextureId) -> Option<Texture<'r>> {
+        CanvasRenderer::unregister_texture(self, id)
+    }
+
+    fn render_draw_data(&mut self, canvas: &mut WindowCanvas, draw_data: &DrawData) -> Result<(), Box<dyn Error>> {
+        CanvasRenderer::render_draw_data(self, canvas, draw_data)
+    }
+
+    /// Textures are lost when the render device is reset (`Event::RenderDeviceReset`):
+    /// registered textures are released, and the font atlas uploaded again.
+    fn recreate_device_objects(
+        &mut self,
+        _device: &Self::Device,
+        imgui_context: &mut imgui::Context,
+    ) -> Result<(), Box<dyn Error>> {
+        self.frame_textures.clear();
+        self.textures.truncate(1);
+        self.reload_font_texture(imgui_context)
+    }
+}
+
+impl<T> CanvasRenderer<'_, T> {
+    /// Releases the textures pushed for the current frame only.
+    fn release_frame_textures(&mut self) {
+        for id in self.frame_textures.drain(..) {
+            self.textures[id.id()] = None;
+        }
+    }
+}
+
+/// Uploads the ImGui font atlas to a static texture.
+fn create_font_texture<'r, T>(
+    texture_creator: &'r TextureCreator<T>,
+    imgui_context: &mut imgui::Context,
+) -> Result<Texture<'r>, Box<dyn Error>> {
+    let font_atlas = imgui_context.fonts().build_rgba32_texture();
+
+    let mut font_texture =
+        texture_creator.create_texture_static(PixelFormat::RGBA32, font_atlas.width, font_atlas.height)?;
+    font_texture.update(None, font_atlas.data, font_atlas.width as usize * 4)?;
+    font_texture.set_blend_mode(BlendMode::Blend);
+
+    // Assign the font texture ID (hardcoded to 0)
+    imgui_context.fonts().tex_id = TextureId::from(0);
+
+    Ok(font_texture)
+}
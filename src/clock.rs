@@ -0,0 +1,223 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// Time source of `Platform::prepare_frame`, which feeds imgui the time elapsed since
+/// the previous frame (animations, double-clicks, tooltips, key repeat...).
+///
+/// Replace the default `RealClock` with `FixedClock` or `ManualClock` to make frames
+/// reproducible in tests and replays.
+pub trait Clock {
+    /// Returns the time elapsed since the previous call, called once per frame.
+    fn delta_time(&mut self) -> Duration;
+}
+
+impl<C: Clock + ?Sized> Clock for Box<C> {
+    fn delta_time(&mut self) -> Duration {
+        (**self).delta_time()
+    }
+}
+
+/// Wall clock time, measured with `Instant`.
+#[derive(Debug, Clone)]
+pub struct RealClock {
+    last_frame: Instant,
+}
+
+impl Default for RealClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RealClock {
+    pub fn new() -> Self {
+        Self {
+            last_frame: Instant::now(),
+        }
+    }
+}
+
+impl Clock for RealClock {
+    fn delta_time(&mut self) -> Duration {
+        let now = Instant::now();
+        let delta = now.duration_since(self.last_frame);
+        self.last_frame = now;
+        delta
+    }
+}
+
+/// Wall clock time, measured with SDL's high resolution performance counter.
+#[derive(Debug, Clone)]
+pub struct SdlClock {
+    last_frame: u64,
+    /// Counter ticks per second.
+    frequency: u64,
+}
+
+impl Default for SdlClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SdlClock {
+    pub fn new() -> Self {
+        Self {
+            last_frame: sdl3::timer::performance_counter(),
+            frequency: sdl3::timer::performance_frequency().max(1),
+        }
+    }
+}
+
+impl Clock for SdlClock {
+    fn delta_time(&mut self) -> Duration {
+        let now = sdl3::timer::performance_counter();
+        let ticks = now.saturating_sub(self.last_frame);
+        self.last_frame = now;
+        ticks_to_duration(ticks, self.frequency)
+    }
+}
+
+/// Converts performance counter ticks to a duration, without overflowing on fast counters.
+fn ticks_to_duration(ticks: u64, frequency: u64) -> Duration {
+    let seconds = ticks / frequency;
+    let nanos = u128::from(ticks % frequency) * 1_000_000_000 / u128::from(frequency);
+    Duration::new(seconds, nanos as u32)
+}
+
+/// The same time step every frame, whatever the actual frame rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock {
+    step: Duration,
+}
+
+impl FixedClock {
+    pub fn new(step: Duration) -> Self {
+        Self { step }
+    }
+
+    /// Steps of `1 / fps` seconds.
+    pub fn from_fps(fps: f32) -> Self {
+        Self::new(Duration::from_secs_f32(1.0 / fps.max(f32::EPSILON)))
+    }
+}
+
+impl Clock for FixedClock {
+    fn delta_time(&mut self) -> Duration {
+        self.step
+    }
+}
+
+/// Time only passes when advanced explicitly.
+///
+/// The clock is a shared handle: keep a clone to advance the one given to the platform.
+///
+/// # Example
+/// ```rust,no_run
+/// # let mut imgui = imgui::Context::create();
+/// use imgui_sdl3::{clock::ManualClock, platform::Platform};
+/// use std::time::Duration;
+///
+/// let clock = ManualClock::new();
+/// let platform = Platform::new(&mut imgui).with_clock(clock.clone());
+///
+/// // ... before preparing the next frame
+/// clock.advance(Duration::from_millis(500));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    /// Nanoseconds advanced since the previous frame.
+    pending: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `delta` to the time step of the next frame.
+    pub fn advance(&self, delta: Duration) {
+        let nanos = u64::try_from(delta.as_nanos()).unwrap_or(u64::MAX);
+        self.pending.fetch_add(nanos, Ordering::Relaxed);
+    }
+}
+
+impl Clock for ManualClock {
+    /// Returns the time advanced since the previous call, `Duration::ZERO` if none.
+    ///
+    /// imgui rejects a zero time step: `Io::update_delta_time` (which `Platform` feeds it
+    /// through) clamps it to the smallest positive one, so frames without any advance
+    /// leave timers as they are.
+    fn delta_time(&mut self) -> Duration {
+        Duration::from_nanos(self.pending.swap(0, Ordering::Relaxed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::imgui_lock;
+
+    #[test]
+    fn fixed_clock() {
+        let mut clock = FixedClock::new(Duration::from_millis(20));
+        assert_eq!(clock.delta_time(), Duration::from_millis(20));
+        assert_eq!(clock.delta_time(), Duration::from_millis(20));
+
+        let step = FixedClock::from_fps(60.0).delta_time();
+        assert!((step.as_secs_f64() - 1.0 / 60.0).abs() < 1e-6);
+
+        // A zero frame rate must not divide by zero
+        assert!(FixedClock::from_fps(0.0).delta_time() > Duration::ZERO);
+    }
+
+    #[test]
+    fn manual_clock_steps() {
+        let handle = ManualClock::new();
+        let mut clock = handle.clone();
+        assert_eq!(clock.delta_time(), Duration::ZERO);
+
+        handle.advance(Duration::from_millis(300));
+        handle.advance(Duration::from_millis(200));
+        assert_eq!(clock.delta_time(), Duration::from_millis(500));
+
+        // Advances are consumed by the frame that reads them
+        assert_eq!(clock.delta_time(), Duration::ZERO);
+
+        handle.advance(Duration::from_nanos(1));
+        assert_eq!(clock.delta_time(), Duration::from_nanos(1));
+    }
+
+    #[test]
+    fn zero_step_is_clamped_by_imgui() {
+        let _lock = imgui_lock();
+        let mut imgui = imgui::Context::create();
+        let mut clock = ManualClock::new();
+
+        imgui.io_mut().update_delta_time(clock.delta_time());
+        assert!(imgui.io().delta_time > 0.0);
+    }
+
+    #[test]
+    fn sdl_clock_ticks() {
+        assert_eq!(ticks_to_duration(0, 1_000), Duration::ZERO);
+        assert_eq!(ticks_to_duration(2_500, 1_000), Duration::from_millis(2_500));
+        assert_eq!(ticks_to_duration(1, 3), Duration::from_nanos(333_333_333));
+
+        // Nanosecond counters (and faster ones) must not overflow
+        let frequency = 10_000_000_000;
+        assert_eq!(
+            ticks_to_duration(frequency + frequency / 4, frequency),
+            Duration::from_millis(1_250)
+        );
+        assert_eq!(
+            ticks_to_duration(u64::MAX, 1_000_000_000),
+            Duration::new(u64::MAX / 1_000_000_000, (u64::MAX % 1_000_000_000) as u32)
+        );
+    }
+}
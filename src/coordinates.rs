@@ -0,0 +1,177 @@
+use imgui::DrawData;
+
+/// Converts between the coordinate spaces of a window:
+///
+/// * points: SDL window coordinates (window size, mouse position, warping, text input area)
+/// * pixels: the drawable area (render targets, scissor rects)
+/// * display: imgui coordinates (`Io::display_size`, `Io::mouse_pos`, draw data)
+///
+/// Display coordinates are points offset by the display position, so that imgui lays out in
+/// points. On high density displays, points and pixels differ by the framebuffer scale (e.g. 2
+/// on a Retina display), whatever the content scale imgui is drawn with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    /// Display coordinates of the upper-left corner of the window.
+    display_pos: [f32; 2],
+    /// Pixels per display unit.
+    framebuffer_scale: [f32; 2],
+}
+
+impl Default for Coordinates {
+    fn default() -> Self {
+        Self {
+            display_pos: [0.0, 0.0],
+            framebuffer_scale: [1.0, 1.0],
+        }
+    }
+}
+
+impl Coordinates {
+    /// Coordinates of a window whose display coordinates are its points, as set up by `Platform`.
+    ///
+    /// Sizes are the window size in points and in pixels, a minimized window keeps a scale of 1.
+    pub fn from_window_size(size: (u32, u32), size_in_pixels: (u32, u32)) -> Self {
+        let scale = |pixels: u32, points: u32| {
+            if pixels == 0 || points == 0 {
+                1.0
+            } else {
+                pixels as f32 / points as f32
+            }
+        };
+
+        Self {
+            framebuffer_scale: [scale(size_in_pixels.0, size.0), scale(size_in_pixels.1, size.1)],
+            ..Self::default()
+        }
+    }
+
+    /// Coordinates of the target draw data is rendered to.
+    pub fn from_draw_data(draw_data: &DrawData) -> Self {
+        Self {
+            display_pos: draw_data.display_pos,
+            framebuffer_scale: draw_data.framebuffer_scale,
+        }
+    }
+
+    /// Pixels per display unit, see `Io::display_framebuffer_scale`.
+    pub fn framebuffer_scale(&self) -> [f32; 2] {
+        self.framebuffer_scale
+    }
+
+    pub fn points_to_display(&self, [x, y]: [f32; 2]) -> [f32; 2] {
+        [x + self.display_pos[0], y + self.display_pos[1]]
+    }
+
+    pub fn display_to_points(&self, [x, y]: [f32; 2]) -> [f32; 2] {
+        [x - self.display_pos[0], y - self.display_pos[1]]
+    }
+
+    pub fn pixels_to_display(&self, [x, y]: [f32; 2]) -> [f32; 2] {
+        [
+            x / self.framebuffer_scale[0] + self.display_pos[0],
+            y / self.framebuffer_scale[1] + self.display_pos[1],
+        ]
+    }
+
+    pub fn display_to_pixels(&self, [x, y]: [f32; 2]) -> [f32; 2] {
+        [
+            (x - self.display_pos[0]) * self.framebuffer_scale[0],
+            (y - self.display_pos[1]) * self.framebuffer_scale[1],
+        ]
+    }
+
+    pub fn points_to_pixels(&self, position: [f32; 2]) -> [f32; 2] {
+        self.display_to_pixels(self.points_to_display(position))
+    }
+
+    pub fn pixels_to_points(&self, position: [f32; 2]) -> [f32; 2] {
+        self.display_to_points(self.pixels_to_display(position))
+    }
+
+    /// Converts an imgui clip rect (`[min_x, min_y, max_x, max_y]`) to the pixels it covers,
+    /// from `min` included to `max` excluded.
+    ///
+    /// Pixels are truncated like the GPU renderer always did, the result may be empty.
+    pub fn clip_rect_to_pixels(&self, [min_x, min_y, max_x, max_y]: [f32; 4]) -> [i32; 4] {
+        let [x, y] = self.display_to_pixels([min_x, min_y]);
+        let width = ((max_x - min_x) * self.framebuffer_scale[0]).max(0.0);
+        let height = ((max_y - min_y) * self.framebuffer_scale[1]).max(0.0);
+
+        [x as i32, y as i32, x as i32 + width as i32, y as i32 + height as i32]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A window at display position (100, 50) on a display with a framebuffer scale of 2.
+    fn high_density() -> Coordinates {
+        Coordinates {
+            display_pos: [100.0, 50.0],
+            framebuffer_scale: [2.0, 2.0],
+        }
+    }
+
+    #[test]
+    fn window_size_scale() {
+        assert_eq!(
+            Coordinates::from_window_size((800, 600), (1600, 1200)).framebuffer_scale(),
+            [2.0, 2.0]
+        );
+        assert_eq!(
+            Coordinates::from_window_size((800, 600), (1200, 600)).framebuffer_scale(),
+            [1.5, 1.0]
+        );
+        // Minimized
+        assert_eq!(
+            Coordinates::from_window_size((0, 0), (0, 0)).framebuffer_scale(),
+            [1.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn display_to_pixels() {
+        let coordinates = Coordinates::from_window_size((800, 600), (1600, 1200));
+        assert_eq!(coordinates.display_to_pixels([0.0, 0.0]), [0.0, 0.0]);
+        assert_eq!(coordinates.display_to_pixels([10.5, 20.0]), [21.0, 40.0]);
+        assert_eq!(coordinates.pixels_to_display([21.0, 40.0]), [10.5, 20.0]);
+
+        let coordinates = high_density();
+        assert_eq!(coordinates.display_to_pixels([100.0, 50.0]), [0.0, 0.0]);
+        assert_eq!(coordinates.display_to_pixels([110.0, 70.0]), [20.0, 40.0]);
+        assert_eq!(coordinates.pixels_to_display([20.0, 40.0]), [110.0, 70.0]);
+    }
+
+    #[test]
+    fn clip_rect_to_pixels() {
+        let coordinates = high_density();
+        assert_eq!(
+            coordinates.clip_rect_to_pixels([100.0, 50.0, 200.0, 150.0]),
+            [0, 0, 200, 200]
+        );
+        assert_eq!(
+            coordinates.clip_rect_to_pixels([110.25, 60.0, 120.5, 61.0]),
+            [20, 20, 40, 22]
+        );
+        // Inverted rects are empty
+        assert_eq!(
+            coordinates.clip_rect_to_pixels([120.0, 60.0, 110.0, 50.0]),
+            [40, 20, 40, 20]
+        );
+    }
+
+    #[test]
+    fn warp_round_trip() {
+        // `Platform` warps the mouse to `Io::mouse_pos` converted to points, and reads it back
+        let coordinates = high_density();
+        let mouse_pos = [123.5, 67.25];
+        let points = coordinates.display_to_points(mouse_pos);
+        assert_eq!(points, [23.5, 17.25]);
+        assert_eq!(coordinates.points_to_display(points), mouse_pos);
+
+        let pixels = coordinates.points_to_pixels(points);
+        assert_eq!(pixels, [47.0, 34.5]);
+        assert_eq!(coordinates.pixels_to_points(pixels), points);
+    }
+}
@@ -0,0 +1,232 @@
+//! Drag-and-drop of files and text from the OS.
+//!
+//! While an OS drag hovers the window, `Platform` submits it to imgui as an external
+//! drag-drop source of type `PAYLOAD_TYPE`, at the drop position. Targets highlight as
+//! usual, and `accept_os_drop` returns the dropped files or text once released.
+//!
+//! # Example
+//! ```rust,no_run
+//! # let ui: &imgui::Ui = unimplemented!();
+//! use imgui_sdl3::dragdrop::{DropPayload, accept_os_drop};
+//!
+//! ui.button("Drop assets here");
+//! if let Some(target) = ui.drag_drop_target() {
+//!     if let Some(DropPayload::Files(paths)) = accept_os_drop(&target) {
+//!         println!("dropped {paths:?}");
+//!     }
+//! }
+//! ```
+
+use std::{
+    ffi::{OsStr, c_void},
+    path::PathBuf,
+};
+
+use imgui::{DragDropFlags, DragDropTarget, Ui};
+
+/// Type of the imgui payload of OS drags.
+pub const PAYLOAD_TYPE: &str = "SDL_OS_DROP";
+
+/// Frames the dropped payload is submitted for at most, waiting for imgui to deliver it.
+const MAX_DELIVERY_FRAMES: u32 = 4;
+
+/// What was dropped from the OS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DropPayload {
+    Files(Vec<PathBuf>),
+    Text(String),
+}
+
+impl DropPayload {
+    /// Encodes the payload as imgui payload data: a tag, then for files their count and each
+    /// path as its length and raw bytes, for text its UTF-8 bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            DropPayload::Files(paths) => {
+                let mut bytes = b"F".to_vec();
+                bytes.extend((paths.len() as u32).to_le_bytes());
+                for path in paths {
+                    let path = path.as_os_str().as_encoded_bytes();
+                    bytes.extend((path.len() as u32).to_le_bytes());
+                    bytes.extend(path);
+                }
+                bytes
+            }
+            DropPayload::Text(text) => [b"T".as_slice(), text.as_bytes()].concat(),
+        }
+    }
+
+    /// Decodes data encoded by `to_bytes`, in this process.
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (tag, mut data) = bytes.split_first()?;
+
+        let read_u32 = |data: &mut &[u8]| {
+            let (value, rest) = data.split_first_chunk()?;
+            *data = rest;
+            Some(u32::from_le_bytes(*value) as usize)
+        };
+
+        match tag {
+            b'F' => {
+                let count = read_u32(&mut data)?;
+                let mut paths = Vec::new();
+                for _ in 0..count {
+                    let len = read_u32(&mut data)?;
+                    let (path, rest) = data.split_at_checked(len)?;
+                    data = rest;
+                    // SAFETY: the bytes come from `as_encoded_bytes` in `to_bytes`, imgui copies them as is
+                    paths.push(PathBuf::from(unsafe { OsStr::from_encoded_bytes_unchecked(path) }));
+                }
+                data.is_empty().then_some(DropPayload::Files(paths))
+            }
+            b'T' => Some(DropPayload::Text(std::str::from_utf8(data).ok()?.to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// A step of a drag from the OS, as handled by `Platform` and recorded in `recording::InputEvent::Drop`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DropEvent {
+    /// The drag entered the window.
+    Begin,
+    File(String),
+    Text(String),
+    /// The drag was dropped, or left the window.
+    Complete,
+}
+
+/// Returns what was dropped from the OS on the current drag-drop target, once released.
+///
+/// The target is highlighted while an OS drag hovers it, as for any imgui drag.
+pub fn accept_os_drop(target: &DragDropTarget<'_>) -> Option<DropPayload> {
+    let payload = unsafe { target.accept_payload_unchecked(PAYLOAD_TYPE, DragDropFlags::empty())? };
+    if payload.data.is_null() {
+        return None;
+    }
+
+    let bytes = unsafe { std::slice::from_raw_parts(payload.data as *const u8, payload.size) };
+    DropPayload::from_bytes(bytes)
+}
+
+/// An OS drag hovering the window, tracked by `Platform`.
+#[derive(Debug, Default)]
+pub(crate) struct OsDrag {
+    files: Vec<PathBuf>,
+    text: String,
+    /// Frames the dropped payload was submitted for, `None` while hovering.
+    dropped_frames: Option<u32>,
+}
+
+impl OsDrag {
+    pub(crate) fn add_file(&mut self, path: &str) {
+        self.files.push(PathBuf::from(path));
+    }
+
+    pub(crate) fn add_text(&mut self, text: &str) {
+        if !self.text.is_empty() {
+            self.text.push('\n');
+        }
+        self.text.push_str(text);
+    }
+
+    /// Marks the drag as dropped, returns `false` if nothing was dropped (e.g. it left the window).
+    pub(crate) fn complete(&mut self) -> bool {
+        self.dropped_frames = Some(0);
+        !self.files.is_empty() || !self.text.is_empty()
+    }
+
+    /// Whether the mouse button held by the drag must be released, on the frame after the drop.
+    pub(crate) fn wants_release(&self) -> bool {
+        self.dropped_frames == Some(1)
+    }
+
+    /// Submits the drag to imgui, returns `false` once it is over.
+    pub(crate) fn submit(&mut self, ui: &Ui) -> bool {
+        let payload = match self.dropped_frames {
+            None => Vec::new(),
+            Some(0) => self.payload().to_bytes(),
+            // Stop once delivered (imgui then clears the payload), or if no target takes it
+            Some(frames)
+                if frames < MAX_DELIVERY_FRAMES && !unsafe { imgui::sys::igGetDragDropPayload() }.is_null() =>
+            {
+                self.payload().to_bytes()
+            }
+            Some(_) => return false,
+        };
+
+        // The OS draws its own drag image
+        let source = ui
+            .drag_drop_source_config(PAYLOAD_TYPE)
+            .flags(DragDropFlags::SOURCE_EXTERN | DragDropFlags::SOURCE_NO_PREVIEW_TOOLTIP);
+        let (data, size) = if payload.is_empty() {
+            (std::ptr::null(), 0)
+        } else {
+            (payload.as_ptr() as *const c_void, payload.len())
+        };
+        // imgui copies the payload data
+        drop(unsafe { source.begin_payload_unchecked(data, size) });
+
+        if let Some(frames) = &mut self.dropped_frames {
+            *frames += 1;
+        }
+        true
+    }
+
+    fn payload(&self) -> DropPayload {
+        if self.files.is_empty() {
+            DropPayload::Text(self.text.clone())
+        } else {
+            DropPayload::Files(self.files.clone())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(payload: DropPayload) {
+        assert_eq!(DropPayload::from_bytes(&payload.to_bytes()), Some(payload));
+    }
+
+    #[test]
+    fn files_round_trip() {
+        round_trip(DropPayload::Files(Vec::new()));
+        round_trip(DropPayload::Files(vec![PathBuf::new()]));
+        round_trip(DropPayload::Files(vec![
+            PathBuf::from("/home/user/a.png"),
+            PathBuf::from("C:\\Users\\user\\b c.txt"),
+            PathBuf::from("/tmp/héllo 🦀"),
+        ]));
+    }
+
+    #[test]
+    fn text_round_trip() {
+        round_trip(DropPayload::Text(String::new()));
+        round_trip(DropPayload::Text("line\0with nul\nand 🦀".to_string()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8_paths_round_trip() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = PathBuf::from(OsStr::from_bytes(b"/tmp/\xff\xfe.bin"));
+        assert!(path.to_str().is_none());
+        round_trip(DropPayload::Files(vec![path]));
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        assert_eq!(DropPayload::from_bytes(b""), None);
+        assert_eq!(DropPayload::from_bytes(b"X"), None);
+        assert_eq!(DropPayload::from_bytes(b"T\xff"), None);
+
+        // Truncated count, path and trailing data
+        let bytes = DropPayload::Files(vec![PathBuf::from("a.txt")]).to_bytes();
+        assert_eq!(DropPayload::from_bytes(&bytes[..3]), None);
+        assert_eq!(DropPayload::from_bytes(&bytes[..bytes.len() - 1]), None);
+        assert_eq!(DropPayload::from_bytes(&[bytes.as_slice(), b"x"].concat()), None);
+    }
+}
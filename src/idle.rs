@@ -0,0 +1,320 @@
+use std::{ptr, sync::Arc, time::Duration};
+
+use sdl3::{
+    EventPump, EventSubsystem,
+    event::{Event, EventSender},
+};
+
+use crate::clock::{Clock, RealClock};
+
+/// Frames rendered after an input event, so that ImGui settles (hover, release, layout).
+const ACTIVE_FRAMES_AFTER_EVENT: u32 = 3;
+
+/// Longest wait while a text field is focused, so that the cursor keeps blinking.
+const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Power-saving settings, see `ImGuiSdl3::wait_event`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IdleConfig {
+    /// Whether the frame loop may sleep while nothing changes, disabled by default.
+    pub enabled: bool,
+    /// Frame rate kept while idle, so that time-based content (tooltips, clocks) still updates.
+    pub min_fps: f32,
+}
+
+impl Default for IdleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_fps: 1.0,
+        }
+    }
+}
+
+impl IdleConfig {
+    /// Enables idling, with the default minimum frame rate.
+    pub fn enabled() -> Self {
+        Self {
+            enabled: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_min_fps(mut self, min_fps: f32) -> Self {
+        self.min_fps = min_fps;
+        self
+    }
+}
+
+/// Wakes a frame loop sleeping in `ImGuiSdl3::wait_event`, from any thread.
+///
+/// # Example
+/// ```rust,no_run
+/// # let mut imgui: imgui_sdl3::ImGuiSdl3 = unimplemented!();
+/// # let event_subsystem: sdl3::EventSubsystem = unimplemented!();
+/// let waker = imgui.idle_waker(&event_subsystem).unwrap();
+///
+/// std::thread::spawn(move || {
+///     // ... once a background job has something to show
+///     waker.wake().unwrap();
+/// });
+/// ```
+#[derive(Clone)]
+pub struct IdleWaker {
+    sender: Arc<EventSender>,
+    event_type: u32,
+}
+
+impl IdleWaker {
+    /// Pushes a wake-up event to the SDL event queue.
+    pub fn wake(&self) -> Result<(), sdl3::Error> {
+        self.sender.push_event(Event::User {
+            timestamp: 0,
+            window_id: 0,
+            type_: self.event_type,
+            code: 0,
+            data1: ptr::null_mut(),
+            data2: ptr::null_mut(),
+        })
+    }
+}
+
+/// Tracks whether ImGui needs another frame.
+pub(crate) struct IdleTracker {
+    config: IdleConfig,
+    /// Frames to render before going idle.
+    active_frames: u32,
+    /// A text field is focused, its cursor blinks.
+    text_input: bool,
+    /// Measures the time between frames, the wall clock by default.
+    clock: Box<dyn Clock>,
+    /// Time read from `clock` so far, the reference of `last_frame` and `wake_at`.
+    now: Duration,
+    last_frame: Duration,
+    /// Frame requested with `wake_after`.
+    wake_at: Option<Duration>,
+    /// User event type of `IdleWaker`, registered on first use.
+    wake_event: Option<u32>,
+}
+
+impl IdleTracker {
+    pub(crate) fn new(config: IdleConfig) -> Self {
+        Self {
+            config,
+            active_frames: ACTIVE_FRAMES_AFTER_EVENT,
+            text_input: false,
+            clock: Box::new(RealClock::new()),
+            now: Duration::ZERO,
+            last_frame: Duration::ZERO,
+            wake_at: None,
+            wake_event: None,
+        }
+    }
+
+    /// Replaces the clock idle timeouts are measured with. SDL waits in real time, so only
+    /// tests measure them with another clock.
+    #[cfg(test)]
+    pub(crate) fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    pub(crate) fn config(&self) -> IdleConfig {
+        self.config
+    }
+
+    pub(crate) fn set_config(&mut self, config: IdleConfig) {
+        self.config = config;
+    }
+
+    /// Keeps rendering for at least `frames` frames.
+    pub(crate) fn request_frames(&mut self, frames: u32) {
+        self.active_frames = self.active_frames.max(frames);
+    }
+
+    /// Renders a frame once `delay` has passed, e.g. when a timer expires.
+    pub(crate) fn wake_after(&mut self, delay: Duration) {
+        let wake_at = self.now() + delay;
+        self.wake_at = Some(self.wake_at.map_or(wake_at, |current| current.min(wake_at)));
+    }
+
+    pub(crate) fn on_event(&mut self) {
+        self.request_frames(ACTIVE_FRAMES_AFTER_EVENT);
+    }
+
+    /// Updates the state once a frame is rendered, `busy` is set while something
+    /// outside of ImGui is about to change (e.g. textures loading).
+    pub(crate) fn on_frame(&mut self, io: &imgui::Io, busy: bool) {
+        self.active_frames = self.active_frames.saturating_sub(1);
+        self.last_frame = self.now();
+        self.text_input = io.want_text_input;
+        self.wake_at = self.wake_at.filter(|&wake_at| wake_at > self.last_frame);
+
+        // Dragging and keyboard navigation animate without new events
+        if busy || io.nav_active || io.mouse_down.iter().any(|&down| down) {
+            self.request_frames(1);
+        }
+    }
+
+    pub(crate) fn needs_frame(&self) -> bool {
+        !self.config.enabled || self.active_frames > 0
+    }
+
+    /// Waits for an event until the next frame is due.
+    pub(crate) fn wait_event(&mut self, event_pump: &mut EventPump) -> Option<Event> {
+        let timeout = self.next_frame_in();
+        if timeout.is_zero() {
+            return None;
+        }
+
+        event_pump.wait_event_timeout(timeout)
+    }
+
+    /// Time until the next frame is due, zero when it is needed right away.
+    fn next_frame_in(&mut self) -> Duration {
+        if self.needs_frame() {
+            return Duration::ZERO;
+        }
+
+        let mut interval = Duration::from_secs_f32(1.0 / self.config.min_fps.max(f32::EPSILON));
+        if self.text_input {
+            interval = interval.min(CURSOR_BLINK_INTERVAL);
+        }
+
+        let mut due = self.last_frame + interval;
+        if let Some(wake_at) = self.wake_at {
+            due = due.min(wake_at);
+        }
+        due.saturating_sub(self.now())
+    }
+
+    fn now(&mut self) -> Duration {
+        self.now += self.clock.delta_time();
+        self.now
+    }
+
+    pub(crate) fn waker(&mut self, event_subsystem: &EventSubsystem) -> Result<IdleWaker, sdl3::Error> {
+        let event_type = match self.wake_event {
+            Some(event_type) => event_type,
+            None => *self.wake_event.insert(unsafe { event_subsystem.register_event()? }),
+        };
+
+        Ok(IdleWaker {
+            sender: Arc::new(event_subsystem.event_sender()),
+            event_type,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clock::ManualClock, test_utils::imgui_lock};
+
+    fn tracker(clock: &ManualClock) -> IdleTracker {
+        IdleTracker::new(IdleConfig::enabled()).with_clock(clock.clone())
+    }
+
+    fn render_frames(tracker: &mut IdleTracker, io: &imgui::Io, frames: u32) {
+        for _ in 0..frames {
+            tracker.on_frame(io, false);
+        }
+    }
+
+    #[test]
+    fn goes_idle_after_active_frames() {
+        let _lock = imgui_lock();
+        let context = imgui::Context::create();
+        let clock = ManualClock::new();
+        let mut tracker = tracker(&clock);
+
+        render_frames(&mut tracker, context.io(), ACTIVE_FRAMES_AFTER_EVENT - 1);
+        assert!(tracker.needs_frame());
+        render_frames(&mut tracker, context.io(), 1);
+        assert!(!tracker.needs_frame());
+
+        // Loading textures keeps rendering
+        tracker.on_frame(context.io(), true);
+        assert!(tracker.needs_frame());
+        render_frames(&mut tracker, context.io(), 1);
+        assert!(!tracker.needs_frame());
+
+        // Disabled, every frame is needed
+        tracker.set_config(IdleConfig::default());
+        assert!(tracker.needs_frame());
+    }
+
+    #[test]
+    fn wakes_on_input() {
+        let _lock = imgui_lock();
+        let mut context = imgui::Context::create();
+        let clock = ManualClock::new();
+        let mut tracker = tracker(&clock);
+        render_frames(&mut tracker, context.io(), ACTIVE_FRAMES_AFTER_EVENT);
+        assert!(!tracker.needs_frame());
+
+        tracker.on_event();
+        assert_eq!(tracker.next_frame_in(), Duration::ZERO);
+        render_frames(&mut tracker, context.io(), ACTIVE_FRAMES_AFTER_EVENT);
+        assert!(!tracker.needs_frame());
+
+        // A held button keeps rendering, e.g. while dragging
+        context.io_mut().mouse_down[0] = true;
+        render_frames(&mut tracker, context.io(), 10);
+        assert!(tracker.needs_frame());
+        context.io_mut().mouse_down[0] = false;
+        render_frames(&mut tracker, context.io(), 1);
+        assert!(!tracker.needs_frame());
+    }
+
+    #[test]
+    fn idle_frame_deadlines() {
+        let _lock = imgui_lock();
+        let mut context = imgui::Context::create();
+        let clock = ManualClock::new();
+        let mut tracker = tracker(&clock);
+        tracker.set_config(IdleConfig::enabled().with_min_fps(2.0));
+        render_frames(&mut tracker, context.io(), ACTIVE_FRAMES_AFTER_EVENT);
+
+        assert_eq!(tracker.next_frame_in(), Duration::from_millis(500));
+        clock.advance(Duration::from_millis(200));
+        assert_eq!(tracker.next_frame_in(), Duration::from_millis(300));
+        clock.advance(Duration::from_millis(400));
+        assert_eq!(tracker.next_frame_in(), Duration::ZERO);
+
+        // The text cursor blinks faster than the minimum frame rate
+        context.io_mut().want_text_input = true;
+        render_frames(&mut tracker, context.io(), 1);
+        assert_eq!(tracker.next_frame_in(), CURSOR_BLINK_INTERVAL);
+        context.io_mut().want_text_input = false;
+        render_frames(&mut tracker, context.io(), 1);
+    }
+
+    #[test]
+    fn wake_after_deadlines() {
+        let _lock = imgui_lock();
+        let context = imgui::Context::create();
+        let clock = ManualClock::new();
+        let mut tracker = tracker(&clock);
+        render_frames(&mut tracker, context.io(), ACTIVE_FRAMES_AFTER_EVENT);
+
+        // The earliest deadline wins
+        tracker.wake_after(Duration::from_millis(300));
+        tracker.wake_after(Duration::from_millis(250));
+        tracker.wake_after(Duration::from_secs(5));
+        assert_eq!(tracker.next_frame_in(), Duration::from_millis(250));
+
+        clock.advance(Duration::from_millis(100));
+        assert_eq!(tracker.next_frame_in(), Duration::from_millis(150));
+
+        // A frame rendered before the deadline keeps it
+        render_frames(&mut tracker, context.io(), 1);
+        assert_eq!(tracker.next_frame_in(), Duration::from_millis(150));
+
+        // Once reached, the minimum frame rate applies again
+        clock.advance(Duration::from_millis(150));
+        assert_eq!(tracker.next_frame_in(), Duration::ZERO);
+        render_frames(&mut tracker, context.io(), 1);
+        assert_eq!(tracker.next_frame_in(), Duration::from_secs(1));
+    }
+}
@@ -62,7 +62,7 @@
 //!                     // create imgui UI here
 //!                     ui.show_demo_window(&mut true);
 //!                 },
-//!             );
+//!             )?;
 //!
 //!             command_buffer.submit()?;
 //!         } else {
@@ -78,18 +78,59 @@
 #![crate_name = "imgui_sdl3"]
 #![crate_type = "lib"]
 
+pub mod app;
+pub mod async_loader;
+pub mod automation;
+pub mod backend;
+pub mod builder;
+#[cfg(feature = "canvas")]
+pub mod canvas;
+pub mod clock;
+pub mod coordinates;
+pub mod dragdrop;
+pub mod idle;
+#[cfg(feature = "image")]
+pub mod loader;
 pub mod platform;
+pub mod recording;
 pub mod renderer;
+pub mod software;
+pub mod swapchain;
+#[cfg(test)]
+mod test_utils;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod utils;
+pub mod window;
+use std::{error::Error, time::Duration};
+
+pub use app::run;
+
+use async_loader::{AsyncTextureLoader, DecodeResult, LoadState};
+use automation::UiDriver;
+use backend::RendererBackend;
+use builder::ImGuiSdl3Builder;
+use idle::{IdleConfig, IdleTracker, IdleWaker};
 use platform::Platform;
-use renderer::Renderer;
+use recording::FrameInput;
+use renderer::{AlphaMode, GpuTarget, Renderer, TextureOptions};
 use sdl3::gpu::*;
+use swapchain::SwapchainSettings;
+use window::PlatformWindow;
 
 /// Main integration point for using Dear ImGui with SDL3 + GPU rendering
-pub struct ImGuiSdl3 {
-    imgui_context: imgui::Context, // Dear ImGui context (state, configuration, fonts, etc.)
-    platform: Platform,            // Handles SDL3 platform event integration
-    renderer: Renderer,            // Handles GPU rendering of ImGui draw data
+///
+/// The SDL GPU renderer is used by default, any `RendererBackend` can be paired with
+/// the platform integration through `ImGuiSdl3::with_renderer`.
+pub struct ImGuiSdl3<R: RendererBackend = Renderer> {
+    imgui_context: imgui::Context,     // Dear ImGui context (state, configuration, fonts, etc.)
+    platform: Platform,                // Handles SDL3 platform event integration
+    renderer: R,                       // Handles rendering of ImGui draw data
+    present_mode: PresentMode,         // Last present mode applied to the window swapchain
+    composition: SwapchainComposition, // Last composition applied to the window swapchain
+    texture_loader: Option<AsyncTextureLoader>, // Started on the first asynchronous load
+    follow_swapchain_format: bool,     // Whether the renderer tracks the window swapchain format
+    idle: IdleTracker,                 // Decides whether the frame loop may sleep
 }
 
 impl ImGuiSdl3 {
@@ -102,28 +143,15 @@ impl ImGuiSdl3 {
     where
         T: Fn(&mut imgui::Context), // Allows custom configuration of the ImGui context
     {
-        // Create a fresh Dear ImGui context
-        let mut imgui_context = imgui::Context::create();
-
-        // Apply user-provided configuration to the context
-        ctx_configure(&mut imgui_context);
-
-        // Set up SDL3 platform integration (input handling, DPI scaling, etc.)
-        let platform = Platform::new(&mut imgui_context);
-
-        // Set up the GPU renderer for drawing ImGui's UI
-        let renderer = Renderer::new(device, window, &mut imgui_context).unwrap();
-
-        Self {
-            imgui_context,
-            platform,
-            renderer,
-        }
+        ImGuiSdl3Builder::new()
+            .with_context(ctx_configure)
+            .build(device, window)
+            .unwrap()
     }
 
-    /// Pass SDL3 events to ImGui so it can handle inputs (mouse, keyboard, etc.)
-    pub fn handle_event(&mut self, event: &sdl3::event::Event) {
-        self.platform.handle_event(&mut self.imgui_context, event);
+    /// Start configuring a new ImGuiSdl3 instance, see `ImGuiSdl3Builder`
+    pub fn builder<'a>() -> ImGuiSdl3Builder<'a> {
+        ImGuiSdl3Builder::new()
     }
 
     /// Render an ImGui frame
@@ -145,26 +173,461 @@ impl ImGuiSdl3 {
         command_buffer: &mut CommandBuffer,
         color_targets: &[ColorTargetInfo],
         mut draw_callback: T,
-    ) where
+    ) -> Result<(), Box<dyn Error>>
+    where
         T: FnMut(&mut imgui::Ui), // Function that takes a mutable reference to the UI builder
     {
+        // Call the user-provided draw function to build the UI
+        let ui = self.new_frame(sdl_context, device, window, event_pump)?;
+        draw_callback(ui);
+
+        self.render_draw_data(device, command_buffer, color_targets);
+        Ok(())
+    }
+
+    /// Start a new ImGui frame and return the UI builder
+    ///
+    /// The frame must be finished with `render_draw_data`. Unlike `render`, this lets
+    /// the UI be built from several places in between, each with its own `&mut` access.
+    ///
+    /// Fails if the pipeline cannot be rebuilt for a new swapchain format, the previous
+    /// one is kept and the next frame tries again.
+    ///
+    /// - `sdl_context`: SDL3 main context
+    /// - `device`: GPU device handle
+    /// - `window`: SDL3 window reference
+    /// - `event_pump`: SDL3 event pump for polling events
+    pub fn new_frame(
+        &mut self,
+        sdl_context: &mut sdl3::Sdl,
+        device: &sdl3::gpu::Device,
+        window: &sdl3::video::Window,
+        event_pump: &sdl3::EventPump,
+    ) -> Result<&mut imgui::Ui, Box<dyn Error>> {
+        // Rebuild the pipeline if the swapchain format changed behind our back
+        if self.follow_swapchain_format {
+            let format = device.get_swapchain_texture_format(window);
+            self.renderer.set_target_format(device, format)?;
+            self.renderer
+                .set_color_space(device, renderer::swapchain_color_space(format))?;
+        }
+
+        Ok(self.begin_frame(sdl_context, window, event_pump))
+    }
+
+    /// Finish the frame started with `new_frame` and render it
+    ///
+    /// - `device`: GPU device handle
+    /// - `command_buffer`: GPU command buffer for recording draw commands
+    /// - `color_targets`: Color target attachments for rendering
+    pub fn render_draw_data(
+        &mut self,
+        device: &sdl3::gpu::Device,
+        command_buffer: &mut CommandBuffer,
+        color_targets: &[ColorTargetInfo],
+    ) {
+        // Upload the textures decoded in the background before they are drawn
+        if let Some(texture_loader) = &mut self.texture_loader {
+            texture_loader
+                .upload(device, command_buffer, &mut self.renderer)
+                .unwrap();
+        }
+
+        // Render the ImGui draw data to the GPU
+        self.end_frame(GpuTarget {
+            device,
+            command_buffer,
+            color_targets,
+        })
+        .unwrap();
+    }
+
+    /// Query the swapchain parameters supported by the window, along with the current ones
+    pub fn swapchain_settings(&self, device: &Device, window: &sdl3::video::Window) -> SwapchainSettings {
+        SwapchainSettings::query(device, window, self.present_mode, self.composition)
+    }
+
+    /// Change the present mode and composition of the window swapchain
+    ///
+    /// The renderer pipeline is rebuilt for the new swapchain format.
+    pub fn set_swapchain_parameters(
+        &mut self,
+        device: &Device,
+        window: &sdl3::video::Window,
+        present_mode: PresentMode,
+        composition: SwapchainComposition,
+    ) -> Result<(), Box<dyn Error>> {
+        device.set_swapchain_parameters(window, present_mode, composition)?;
+        self.present_mode = present_mode;
+        self.composition = composition;
+
+        self.renderer
+            .set_target_format(device, device.get_swapchain_texture_format(window))?;
+        self.renderer.set_color_space(device, composition)
+    }
+
+    /// Apply swapchain settings edited with `SwapchainSettings::ui`
+    pub fn apply_swapchain_settings(
+        &mut self,
+        device: &Device,
+        window: &sdl3::video::Window,
+        settings: &mut SwapchainSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        self.set_swapchain_parameters(device, window, settings.present_mode(), settings.composition())?;
+        settings.mark_applied();
+        Ok(())
+    }
+
+    /// Set the brightness of the UI in nits when rendering to an HDR swapchain
+    pub fn set_sdr_white_level(&mut self, nits: f32) {
+        self.renderer.set_sdr_white_level(nits);
+    }
+
+    pub fn push_texture(&mut self, texture: Texture<'static>, sampler: Sampler) -> imgui::TextureId {
+        self.renderer.push_texture(texture, sampler)
+    }
+
+    /// Register a texture that keeps its `TextureId` across frames
+    pub fn register_texture(&mut self, texture: Texture<'static>, sampler: Sampler) -> imgui::TextureId {
+        self.renderer.register_texture(texture, sampler)
+    }
+
+    /// Register a texture across frames, with options describing its content
+    pub fn register_texture_with_options(
+        &mut self,
+        texture: Texture<'static>,
+        sampler: Sampler,
+        options: TextureOptions,
+    ) -> imgui::TextureId {
+        self.renderer.register_texture_with_options(texture, sampler, options)
+    }
+
+    /// Upload an SDL surface of any pixel format and register it across frames
+    ///
+    /// The texture is sampled with the same linear sampler as the font atlas.
+    pub fn register_surface(
+        &mut self,
+        device: &Device,
+        surface: &sdl3::surface::SurfaceRef,
+    ) -> Result<imgui::TextureId, Box<dyn Error>> {
+        let command_buffer = device.acquire_command_buffer()?;
+        let (texture, format) = utils::create_texture_from_surface(device, &command_buffer, surface, false)?;
+        command_buffer.submit()?;
+
+        let sampler = self.renderer.default_sampler();
+        Ok(self
+            .renderer
+            .register_texture_with_options(texture, sampler, TextureOptions::for_format(format)))
+    }
+
+    /// Decode an image file and register it as a texture across frames
+    ///
+    /// `Filter::Linear` also generates mipmaps so that thumbnails do not alias, use
+    /// `Filter::Nearest` for pixel art.
+    #[cfg(feature = "image")]
+    pub fn load_texture_from_path(
+        &mut self,
+        device: &Device,
+        path: impl AsRef<std::path::Path>,
+        filter: Filter,
+    ) -> Result<imgui::TextureId, loader::LoadTextureError> {
+        let image = loader::decode_path(path)?;
+        self.register_decoded_image(device, image, filter)
+    }
+
+    /// Decode an image file held in memory and register it as a texture across frames
+    #[cfg(feature = "image")]
+    pub fn load_texture_from_bytes(
+        &mut self,
+        device: &Device,
+        bytes: &[u8],
+        filter: Filter,
+    ) -> Result<imgui::TextureId, loader::LoadTextureError> {
+        let image = loader::decode_bytes(bytes)?;
+        self.register_decoded_image(device, image, filter)
+    }
+
+    #[cfg(feature = "image")]
+    fn register_decoded_image(
+        &mut self,
+        device: &Device,
+        image: utils::DecodedImage,
+        filter: Filter,
+    ) -> Result<imgui::TextureId, loader::LoadTextureError> {
+        let descriptor = image.descriptor.with_mipmaps(filter == Filter::Linear);
+
+        let command_buffer = device.acquire_command_buffer()?;
+        let texture = utils::create_texture_with_format(device, &command_buffer, &descriptor, &image.data)?;
+        command_buffer.submit()?;
+
+        let sampler = utils::create_sampler(device, filter)?;
+        Ok(self
+            .renderer
+            .register_texture_with_options(texture, sampler, TextureOptions::for_format(descriptor.format)))
+    }
+
+    /// Decode an image on a worker thread and register it as a texture across frames
+    ///
+    /// The returned id draws a placeholder until the image is uploaded, which happens
+    /// in the copy pass of the first frame rendered after decoding completes.
+    pub fn load_texture_async<F>(
+        &mut self,
+        device: &Device,
+        filter: Filter,
+        decode: F,
+    ) -> Result<imgui::TextureId, Box<dyn Error>>
+    where
+        F: FnOnce() -> DecodeResult + Send + 'static,
+    {
+        let sampler = utils::create_sampler(device, filter)?;
+        let texture_loader = match &mut self.texture_loader {
+            Some(texture_loader) => texture_loader,
+            None => self.texture_loader.insert(AsyncTextureLoader::new(device)?),
+        };
+
+        Ok(texture_loader.load(&mut self.renderer, sampler, filter == Filter::Linear, decode))
+    }
+
+    /// Decode an image file on a worker thread and register it as a texture across frames
+    #[cfg(feature = "image")]
+    pub fn load_texture_from_path_async(
+        &mut self,
+        device: &Device,
+        path: impl Into<std::path::PathBuf>,
+        filter: Filter,
+    ) -> Result<imgui::TextureId, Box<dyn Error>> {
+        let path = path.into();
+        self.load_texture_async(device, filter, move || {
+            loader::decode_path(path).map_err(|error| error.to_string().into())
+        })
+    }
+
+    /// Query whether a texture started with `load_texture_async` is ready to be drawn
+    pub fn texture_load_state(&self, id: imgui::TextureId) -> LoadState {
+        self.texture_loader
+            .as_ref()
+            .map_or(LoadState::Ready, |texture_loader| texture_loader.state(id))
+    }
+
+    /// Cancel a texture load (e.g. when its thumbnail scrolled out of view) and release its id
+    pub fn cancel_texture_load(&mut self, id: imgui::TextureId) -> bool {
+        self.texture_loader
+            .as_mut()
+            .is_some_and(|texture_loader| texture_loader.cancel(&mut self.renderer, id))
+    }
+
+    /// Release a texture registered with `register_texture`
+    pub fn unregister_texture(&mut self, id: imgui::TextureId) -> Option<(Texture<'static>, Sampler)> {
+        // Make sure a pending load does not land in the slot once it is reused
+        if let Some(texture_loader) = &mut self.texture_loader {
+            texture_loader.forget(id);
+        }
+
+        self.renderer.unregister_texture(id)
+    }
+
+    /// Upload pixels, in the format the texture was registered with, to a region of it (e.g. a video frame)
+    pub fn update_texture(
+        &mut self,
+        device: &Device,
+        id: imgui::TextureId,
+        region: sdl3::rect::Rect,
+        data: &[u8],
+    ) -> Result<(), Box<dyn Error>> {
+        self.renderer.update_texture(device, id, region, data)
+    }
+
+    /// Register a texture whose content needs special handling (e.g. premultiplied alpha)
+    pub fn push_texture_with_options(
+        &mut self,
+        texture: Texture<'static>,
+        sampler: Sampler,
+        options: TextureOptions,
+    ) -> imgui::TextureId {
+        self.renderer.push_texture_with_options(texture, sampler, options)
+    }
+
+    /// Switch between straight and premultiplied alpha output
+    ///
+    /// Use premultiplied output when rendering the UI into an offscreen layer.
+    pub fn set_alpha_mode(&mut self, device: &Device, alpha_mode: AlphaMode) -> Result<(), Box<dyn Error>> {
+        self.renderer.set_alpha_mode(device, alpha_mode)
+    }
+}
+
+impl<R: RendererBackend> ImGuiSdl3<R> {
+    /// Pair the SDL3 platform integration with another renderer backend
+    ///
+    /// The renderer must have been created with `imgui_context`, which uploaded its font atlas.
+    /// Use `ImGuiSdl3Builder::build_with_renderer` to configure the platform as well.
+    pub fn with_renderer(imgui_context: imgui::Context, renderer: R) -> Self {
+        ImGuiSdl3Builder::new().assemble(imgui_context, renderer, false)
+    }
+
+    pub(crate) fn from_parts(
+        imgui_context: imgui::Context,
+        platform: Platform,
+        renderer: R,
+        follow_swapchain_format: bool,
+        idle_config: IdleConfig,
+    ) -> Self {
+        Self {
+            imgui_context,
+            platform,
+            renderer,
+            present_mode: PresentMode::Vsync,
+            composition: SwapchainComposition::Sdr,
+            texture_loader: None,
+            follow_swapchain_format,
+            idle: IdleTracker::new(idle_config),
+        }
+    }
+
+    /// Pass SDL3 events to ImGui so it can handle inputs (mouse, keyboard, etc.)
+    pub fn handle_event(&mut self, event: &sdl3::event::Event) {
+        self.idle.on_event();
+        self.platform.handle_event(&mut self.imgui_context, event);
+    }
+
+    /// Start a new ImGui frame with any renderer backend and return the UI builder
+    ///
+    /// The frame must be finished with `end_frame`.
+    pub fn begin_frame(
+        &mut self,
+        sdl_context: &mut sdl3::Sdl,
+        window: &sdl3::video::Window,
+        event_pump: &sdl3::EventPump,
+    ) -> &mut imgui::Ui {
         // Prepare ImGui for a new frame (update input state, time step, etc.)
         self.platform
             .prepare_frame(sdl_context, &mut self.imgui_context, window, event_pump);
 
         // Start a new ImGui frame and get the UI object
         let ui = self.imgui_context.new_frame();
+        self.platform.submit_os_drag(ui);
+        ui
+    }
 
-        // Call the user-provided draw function to build the UI
-        draw_callback(ui);
+    /// Start a new ImGui frame from a recorded frame instead of live SDL input, see `recording`
+    ///
+    /// The frame must be finished with `end_frame` (or `render_draw_data`).
+    pub fn replay_frame(&mut self, frame: &FrameInput) -> &mut imgui::Ui {
+        self.platform.replay_frame(&mut self.imgui_context, frame);
+        let ui = self.imgui_context.new_frame();
+        self.platform.submit_os_drag(ui);
+        ui
+    }
 
-        // Render the ImGui draw data to the GPU
-        self.renderer
-            .render(device, command_buffer, color_targets, &mut self.imgui_context)
-            .unwrap();
+    /// Drive the UI built by `ui` with scripted inputs, locating items by their ID path, see `automation`
+    ///
+    /// Frames use the current display size (1280x720 if none was set yet) and a fixed time step.
+    pub fn automate<F>(&mut self, ui: F) -> UiDriver<'_, R, F>
+    where
+        F: FnMut(&mut imgui::Ui),
+    {
+        UiDriver::new(self, ui)
     }
 
-    pub fn push_texture(&mut self, texture: Texture<'static>, sampler: Sampler) -> imgui::TextureId {
-        self.renderer.push_texture(texture, sampler)
+    /// Start a new ImGui frame reading the window and mouse state from any `PlatformWindow`
+    ///
+    /// E.g. a `window::MockWindow` in tests. The frame must be finished with `end_frame`.
+    pub fn begin_frame_with<W: PlatformWindow + ?Sized>(&mut self, window: &mut W) -> &mut imgui::Ui {
+        self.platform.prepare_frame_with(&mut self.imgui_context, window);
+        let ui = self.imgui_context.new_frame();
+        self.platform.submit_os_drag(ui);
+        ui
+    }
+
+    /// Finish the frame started with `begin_frame` and render it into the target
+    pub fn end_frame(&mut self, target: R::Target<'_>) -> Result<(), Box<dyn Error>> {
+        let draw_data = self.imgui_context.render();
+        self.renderer.render_draw_data(target, draw_data)?;
+
+        // Keep rendering while textures are loading, so that they show up as soon as they are ready
+        let loading = self
+            .texture_loader
+            .as_ref()
+            .is_some_and(|texture_loader| texture_loader.pending_count() > 0);
+        self.idle.on_frame(self.imgui_context.io(), loading);
+
+        Ok(())
+    }
+
+    /// Upload the font atlas again after fonts were changed through `context_mut`
+    pub fn reload_fonts(&mut self, device: &R::Device) -> Result<(), Box<dyn Error>> {
+        self.renderer.init(device, &mut self.imgui_context)
+    }
+
+    /// Recreate the renderer resources after the device was lost or reset
+    pub fn handle_device_lost(&mut self, device: &R::Device) -> Result<(), Box<dyn Error>> {
+        self.renderer.recreate_device_objects(device, &mut self.imgui_context)
+    }
+
+    /// Wait for the next event while ImGui does not need a new frame
+    ///
+    /// Returns `None` right away when a frame is needed (or idling is disabled), and after
+    /// the timeout derived from `IdleConfig::min_fps` and `wake_after` otherwise. The returned event must be
+    /// handled like the polled ones.
+    pub fn wait_event(&mut self, event_pump: &mut sdl3::EventPump) -> Option<sdl3::event::Event> {
+        self.idle.wait_event(event_pump)
+    }
+
+    /// Whether ImGui needs a new frame (input, dragging, text cursor, loading textures)
+    pub fn needs_frame(&self) -> bool {
+        self.idle.needs_frame()
+    }
+
+    /// Keep rendering for at least `frames` frames, e.g. while an animation plays
+    pub fn request_frames(&mut self, frames: u32) {
+        self.idle.request_frames(frames);
+    }
+
+    /// Render a frame once `delay` has passed, e.g. when a timer shown by the UI expires
+    pub fn wake_after(&mut self, delay: Duration) {
+        self.idle.wake_after(delay);
+    }
+
+    pub fn idle_config(&self) -> IdleConfig {
+        self.idle.config()
+    }
+
+    /// Enable or disable the power-saving idle mode
+    pub fn set_idle_config(&mut self, config: IdleConfig) {
+        self.idle.set_config(config);
+    }
+
+    /// Create a handle waking `wait_event` from other threads through an SDL user event
+    pub fn idle_waker(&mut self, event_subsystem: &sdl3::EventSubsystem) -> Result<IdleWaker, sdl3::Error> {
+        self.idle.waker(event_subsystem)
+    }
+
+    /// Access the Dear ImGui context
+    pub fn context(&self) -> &imgui::Context {
+        &self.imgui_context
+    }
+
+    /// Access the Dear ImGui context mutably (e.g. to change styles or fonts)
+    pub fn context_mut(&mut self) -> &mut imgui::Context {
+        &mut self.imgui_context
+    }
+
+    /// Access the SDL3 platform integration
+    pub fn platform(&self) -> &Platform {
+        &self.platform
+    }
+
+    /// Access the SDL3 platform integration mutably
+    pub fn platform_mut(&mut self) -> &mut Platform {
+        &mut self.platform
+    }
+
+    /// Access the renderer
+    pub fn renderer(&self) -> &R {
+        &self.renderer
+    }
+
+    /// Access the renderer mutably (e.g. to register textures)
+    pub fn renderer_mut(&mut self) -> &mut R {
+        &mut self.renderer
     }
 }
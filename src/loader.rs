@@ -0,0 +1,105 @@
+//! Image file loading, available with the `image` feature.
+//!
+//! Files are decoded with the [`image`] crate: 8-bit and 16-bit images are uploaded as
+//! RGBA8 textures, floating point images (HDR, OpenEXR) as RGBA32 float textures.
+//!
+//! Only the PNG and JPEG decoders are enabled, other formats are decoded once their
+//! `image` features are enabled by the application (e.g. `image = { features = ["exr"] }`).
+
+use std::{error::Error, fmt, path::Path};
+
+use crate::utils::{DecodedImage, PixelFormat, TextureDescriptor};
+use image::{DynamicImage, ImageError, ImageReader};
+
+/// Error returned when loading a texture from an image file.
+#[derive(Debug)]
+pub enum LoadTextureError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The image format (or a feature of it) is not supported.
+    Unsupported(ImageError),
+    /// The file is corrupt, truncated or exceeds the decoder limits.
+    Corrupt(ImageError),
+    /// The texture could not be created or uploaded.
+    Gpu(Box<dyn Error>),
+}
+
+impl fmt::Display for LoadTextureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadTextureError::Io(e) => write!(f, "failed to read image: {e}"),
+            LoadTextureError::Unsupported(e) => write!(f, "unsupported image: {e}"),
+            LoadTextureError::Corrupt(e) => write!(f, "corrupt image: {e}"),
+            LoadTextureError::Gpu(e) => write!(f, "failed to upload image: {e}"),
+        }
+    }
+}
+
+impl Error for LoadTextureError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadTextureError::Io(e) => Some(e),
+            LoadTextureError::Unsupported(e) | LoadTextureError::Corrupt(e) => Some(e),
+            LoadTextureError::Gpu(e) => Some(e.as_ref()),
+        }
+    }
+}
+
+impl From<ImageError> for LoadTextureError {
+    fn from(error: ImageError) -> Self {
+        match error {
+            ImageError::IoError(e) => LoadTextureError::Io(e),
+            ImageError::Unsupported(_) => LoadTextureError::Unsupported(error),
+            _ => LoadTextureError::Corrupt(error),
+        }
+    }
+}
+
+impl From<std::io::Error> for LoadTextureError {
+    fn from(error: std::io::Error) -> Self {
+        LoadTextureError::Io(error)
+    }
+}
+
+impl From<Box<dyn Error>> for LoadTextureError {
+    fn from(error: Box<dyn Error>) -> Self {
+        LoadTextureError::Gpu(error)
+    }
+}
+
+impl From<sdl3::Error> for LoadTextureError {
+    fn from(error: sdl3::Error) -> Self {
+        LoadTextureError::Gpu(Box::new(error))
+    }
+}
+
+/// Decodes an image file held in memory, guessing its format from its content.
+pub fn decode_bytes(bytes: &[u8]) -> Result<DecodedImage, LoadTextureError> {
+    Ok(decoded(image::load_from_memory(bytes)?))
+}
+
+/// Decodes an image file, guessing its format from its content rather than its extension.
+pub fn decode_path(path: impl AsRef<Path>) -> Result<DecodedImage, LoadTextureError> {
+    let image = ImageReader::open(path)?.with_guessed_format()?.decode()?;
+    Ok(decoded(image))
+}
+
+fn decoded(image: DynamicImage) -> DecodedImage {
+    let (width, height) = (image.width(), image.height());
+
+    match image {
+        DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_) => DecodedImage {
+            descriptor: TextureDescriptor::new(width, height, PixelFormat::Rgba32Float),
+            data: image
+                .to_rgba32f()
+                .into_raw()
+                .into_iter()
+                .flat_map(f32::to_ne_bytes)
+                .collect(),
+        },
+        _ => DecodedImage {
+            descriptor: TextureDescriptor::new(width, height, PixelFormat::Rgba8),
+            data: image.to_rgba8().into_raw(),
+        },
+    }
+}
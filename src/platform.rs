@@ -1,14 +1,46 @@
-use std::time::Instant;
+use std::{cell::Cell, collections::HashMap, error::Error};
 
-use imgui::{BackendFlags, ConfigFlags, Context, Io, MouseCursor};
+use imgui::{BackendFlags, ConfigFlags, Context, Io, MouseButton};
 use sdl3::{
     EventPump, Sdl,
+    clipboard::ClipboardUtil,
     event::Event,
     keyboard::{Mod, Scancode},
-    mouse::{Cursor, MouseState, SystemCursor},
     video::Window,
 };
 
+use crate::{
+    clock::{Clock, RealClock},
+    coordinates::Coordinates,
+    dragdrop::{DropEvent, OsDrag},
+    recording::{FrameInput, InputEvent, Recorder, Recording},
+    window::{CursorId, CursorImage, CursorShape, PlatformWindow, SdlWindow},
+};
+
+/// Position imgui takes as "no mouse".
+const NO_MOUSE_POS: [f32; 2] = [-f32::MAX, -f32::MAX];
+
+thread_local! {
+    /// Text input area (position and line height, in display coordinates) imgui requested
+    /// when rendering, applied by the next `Platform::prepare_frame_with`.
+    static TEXT_INPUT_AREA: Cell<Option<([f32; 2], f32)>> = const { Cell::new(None) };
+
+    /// Custom cursor imgui code requested with `window::set_custom_cursor`, taken by the
+    /// next `Platform::prepare_frame_with`.
+    pub(crate) static CUSTOM_CURSOR: Cell<Option<CursorId>> = const { Cell::new(None) };
+}
+
+/// How imgui gets the mouse while the application has enabled SDL relative mouse mode,
+/// e.g. a game grabbing the mouse to control its camera.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RelativeMouse {
+    /// imgui gets no mouse input, held buttons are released.
+    #[default]
+    Suspend,
+    /// imgui gets a cursor moved by the relative motion, which it draws itself.
+    VirtualCursor,
+}
+
 /// sdl3 backend platform state.
 ///
 /// A backend platform handles window/input device events and manages their
@@ -20,8 +52,28 @@ use sdl3::{
 /// 2. Pass events to the platform (every frame)
 /// 3. Call frame preparation callback (every frame)
 pub struct Platform {
-    cursor_instance: Option<Cursor>, /* to avoid dropping cursor instances */
-    last_frame: Instant,
+    /// Window of the last `prepare_frame` call, which keeps the cursor instance alive.
+    sdl_window: Option<SdlWindow>,
+    /// Time source of the frame time step, the wall clock by default.
+    clock: Box<dyn Clock>,
+    /// Records the inputs and frames fed to imgui, while recording.
+    recorder: Option<Recorder>,
+    /// Custom cursors imgui code can request with `window::set_custom_cursor`.
+    custom_cursors: HashMap<CursorId, CursorImage>,
+    next_cursor_id: u32,
+    /// Custom cursor requested during the last frame.
+    custom_cursor: Option<CursorId>,
+    relative_mouse: RelativeMouse,
+    /// Whether relative mouse mode was enabled on the last frame.
+    relative_mode: bool,
+    /// Coordinate spaces of the window, as of the last frame.
+    coordinates: Coordinates,
+    /// Position of the virtual cursor in relative mode, in display coordinates.
+    virtual_mouse_pos: [f32; 2],
+    /// Whether `Io::mouse_draw_cursor` was enabled for the virtual cursor, and must be restored.
+    draws_virtual_cursor: bool,
+    /// Drag from the OS hovering the window, see `dragdrop`.
+    os_drag: Option<OsDrag>,
     /// Last known content (DPI) scale factor to avoid applying scaling
     /// every frame (which would accumulate and produce invalid style values).
     hidpi_factor: f32,
@@ -40,30 +92,173 @@ impl Platform {
 
         io.backend_flags.insert(BackendFlags::HAS_MOUSE_CURSORS);
         io.backend_flags.insert(BackendFlags::HAS_SET_MOUSE_POS);
+        io.set_platform_ime_data_fn = Some(set_platform_ime_data);
 
         // io.config_flags.insert(ConfigFlags::DOCKING_ENABLE);
 
-        io.backend_flags.insert(imgui::BackendFlags::RENDERER_HAS_VTX_OFFSET);
-
         Platform {
-            cursor_instance: None,
-            last_frame: Instant::now(),
+            sdl_window: None,
+            clock: Box::new(RealClock::new()),
+            recorder: None,
+            custom_cursors: HashMap::new(),
+            next_cursor_id: 0,
+            custom_cursor: None,
+            relative_mouse: RelativeMouse::default(),
+            relative_mode: false,
+            coordinates: Coordinates::default(),
+            virtual_mouse_pos: NO_MOUSE_POS,
+            draws_virtual_cursor: false,
+            os_drag: None,
             hidpi_factor: 1.0,
         }
     }
 
+    /// Replaces the time source of the frame time step, see `Clock`.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.set_clock(clock);
+        self
+    }
+
+    /// Replaces the time source of the frame time step, see `Clock`.
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = Box::new(clock);
+    }
+
+    /// Sets how imgui gets the mouse in relative mouse mode, see `RelativeMouse`.
+    pub fn with_relative_mouse(mut self, relative_mouse: RelativeMouse) -> Self {
+        self.relative_mouse = relative_mouse;
+        self
+    }
+
+    /// Sets how imgui gets the mouse in relative mouse mode, see `RelativeMouse`.
+    pub fn set_relative_mouse(&mut self, relative_mouse: RelativeMouse) {
+        self.relative_mouse = relative_mouse;
+    }
+
+    /// Coordinate spaces of the window as of the last prepared frame, e.g. to convert
+    /// imgui positions to SDL window points.
+    pub fn coordinates(&self) -> Coordinates {
+        self.coordinates
+    }
+
+    /// Starts recording the inputs and frames fed to imgui, see `recording`.
+    ///
+    /// A recording already in progress is discarded.
+    pub fn start_recording(&mut self) {
+        self.recorder = Some(Recorder::new());
+    }
+
+    /// Stops recording, returning the frames prepared since `start_recording`.
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        self.recorder.take().map(Recorder::finish)
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Registers a custom color cursor, which imgui code requests with `window::set_custom_cursor`.
+    ///
+    /// Fails if the hotspot is outside of the image.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # let mut platform: imgui_sdl3::platform::Platform = unimplemented!();
+    /// # let ui: &imgui::Ui = unimplemented!();
+    /// use imgui_sdl3::{software::Image, window::{CursorImage, set_custom_cursor}};
+    ///
+    /// let pixels = vec![255; 16 * 16 * 4];
+    /// let eyedropper = platform.register_cursor(CursorImage {
+    ///     image: Image::from_rgba(16, 16, pixels).unwrap(),
+    ///     hotspot: [0, 15],
+    /// })
+    /// .unwrap();
+    ///
+    /// // ... while building the UI
+    /// if ui.is_item_hovered() {
+    ///     set_custom_cursor(ui, eyedropper);
+    /// }
+    /// ```
+    pub fn register_cursor(&mut self, cursor: CursorImage) -> Result<CursorId, Box<dyn Error>> {
+        let [x, y] = cursor.hotspot;
+        let (width, height) = (cursor.image.width(), cursor.image.height());
+        if x < 0 || y < 0 || x as u32 >= width || y as u32 >= height {
+            return Err(format!("cursor hotspot ({x}, {y}) is outside of the {width}x{height} image").into());
+        }
+
+        let id = CursorId(self.next_cursor_id);
+        self.next_cursor_id += 1;
+        self.custom_cursors.insert(id, cursor);
+        Ok(id)
+    }
+
+    /// Removes a custom cursor, requesting it afterwards shows the arrow.
+    pub fn unregister_cursor(&mut self, id: CursorId) -> Option<CursorImage> {
+        if let Some(sdl_window) = &mut self.sdl_window {
+            sdl_window.release_cursor(id);
+        }
+        self.custom_cursors.remove(&id)
+    }
+
     /// Handles a SDL event.
     ///
     /// This function performs the following actions (depends on the event):
     ///
     /// * keyboard state is updated
     /// * mouse state is updated
+    /// * files and text dragged from the OS are tracked, see `submit_os_drag`
     pub fn handle_event(&mut self, context: &mut Context, event: &Event) -> bool {
         let io = context.io_mut();
 
         match *event {
+            Event::MouseMotion { xrel, yrel, .. } if self.relative_mode => {
+                if self.relative_mouse != RelativeMouse::VirtualCursor {
+                    return false;
+                }
+
+                let [x, y] = self.virtual_mouse_pos;
+                // Display coordinates are points, relative motion is the same in both
+                let [width, height] = io.display_size;
+                self.virtual_mouse_pos = [(x + xrel).clamp(0.0, width), (y + yrel).clamp(0.0, height)];
+                true
+            }
+
+            Event::MouseWheel { .. } | Event::MouseButtonDown { .. } | Event::MouseButtonUp { .. }
+                if self.relative_mode && self.relative_mouse == RelativeMouse::Suspend =>
+            {
+                false
+            }
+
+            Event::DropBegin { .. } => {
+                self.send_input(io, InputEvent::Drop(DropEvent::Begin));
+                true
+            }
+
+            // sdl3-rs does not know this event yet, the position is read in `prepare_frame_with`
+            Event::Unknown { type_, .. } if type_ == sdl3::sys::events::SDL_EVENT_DROP_POSITION.0 => {
+                if self.os_drag.is_none() {
+                    self.send_input(io, InputEvent::Drop(DropEvent::Begin));
+                }
+                true
+            }
+
+            Event::DropFile { ref filename, .. } => {
+                self.send_input(io, InputEvent::Drop(DropEvent::File(filename.clone())));
+                true
+            }
+
+            Event::DropText { ref filename, .. } => {
+                self.send_input(io, InputEvent::Drop(DropEvent::Text(filename.clone())));
+                true
+            }
+
+            Event::DropComplete { .. } => {
+                self.send_input(io, InputEvent::Drop(DropEvent::Complete));
+                true
+            }
+
             Event::MouseWheel { x, y, .. } => {
-                io.add_mouse_wheel_event([x, y]);
+                self.send_input(io, InputEvent::MouseWheel([x, y]));
                 true
             }
 
@@ -78,7 +273,7 @@ impl Platform {
             }
 
             Event::TextInput { ref text, .. } => {
-                text.chars().for_each(|c| io.add_input_character(c));
+                self.send_input(io, InputEvent::Text(text.clone()));
                 true
             }
 
@@ -87,8 +282,8 @@ impl Platform {
                 keymod,
                 ..
             } => {
-                handle_key_modifier(io, &keymod);
-                handle_key(io, &key, true);
+                self.handle_key_modifier(io, &keymod);
+                self.handle_key(io, &key, true);
                 true
             }
 
@@ -97,8 +292,8 @@ impl Platform {
                 keymod,
                 ..
             } => {
-                handle_key_modifier(io, &keymod);
-                handle_key(io, &key, false);
+                self.handle_key_modifier(io, &keymod);
+                self.handle_key(io, &key, false);
                 true
             }
 
@@ -115,68 +310,158 @@ impl Platform {
     /// * mouse cursor is repositioned (if requested by imgui-rs)
     /// * current mouse cursor position is passed to imgui-rs
     /// * changes mouse cursor icon (if requested by imgui-rs)
-    pub fn prepare_frame(&mut self, sdl: &mut Sdl, context: &mut Context, window: &Window, event_pump: &EventPump) {
-        let content_scale: f32 = window.get_display().and_then(|d| d.get_content_scale()).unwrap_or(1.0);
-        // Apply incremental scaling relative to the last known hi-dpi factor to
-        // avoid accumulating ScaleAllSizes every frame which can shrink sizes
-        // below allowed minima and trigger ImGui sanity checks.
-        let scale_factor = content_scale / self.hidpi_factor;
-        if (scale_factor - 1.0).abs() > f32::EPSILON {
-            context.style_mut().scale_all_sizes(scale_factor);
-            self.hidpi_factor = content_scale;
-        }
+    /// * moves the IME candidate window next to the text being edited
+    ///
+    /// When imgui draws the cursor (`Io::mouse_draw_cursor`), the system cursor is hidden
+    /// and imgui loses the mouse once it leaves the window, so that no stale cursor is drawn.
+    /// In relative mouse mode, the system cursor is left to the application and imgui gets
+    /// the mouse as set with `set_relative_mouse`.
+    ///
+    /// The event pump is no longer needed, it is only kept for compatibility.
+    pub fn prepare_frame(&mut self, sdl: &mut Sdl, context: &mut Context, window: &Window, _event_pump: &EventPump) {
+        // Reuse the SDL window of the previous frame, which keeps the cursors alive
+        let mut sdl_window = match self.sdl_window.take() {
+            Some(sdl_window) if sdl_window.window().id() == window.id() => sdl_window,
+            _ => SdlWindow::new(sdl, window),
+        };
 
-        let mouse_cursor = context.mouse_cursor();
-        let io = context.io_mut();
+        self.prepare_frame_with(context, &mut sdl_window);
+        self.sdl_window = Some(sdl_window);
+    }
 
-        // Update delta time
-        let now = Instant::now();
-        io.update_delta_time(now.duration_since(self.last_frame));
-        self.last_frame = now;
+    /// Frame preparation callback reading the window and mouse state from any `PlatformWindow`.
+    ///
+    /// See `prepare_frame`, which calls it with an `SdlWindow`.
+    pub fn prepare_frame_with<W: PlatformWindow + ?Sized>(&mut self, context: &mut Context, window: &mut W) {
+        let relative_mode = window.relative_mouse_mode();
+        if relative_mode != self.relative_mode {
+            self.toggle_relative_mode(context, window, relative_mode);
+        }
 
-        let mouse_state = MouseState::new(event_pump);
         let window_size = window.size();
-        let window_drawable_size = window.size_in_pixels();
+        let coordinates = Coordinates::from_window_size(window_size, window.size_in_pixels());
+        self.coordinates = coordinates;
+
+        // Set mouse position if requested by imgui-rs
+        let io = context.io();
+        let relative_mouse = relative_mode.then_some(self.relative_mouse);
+        if io.want_set_mouse_pos {
+            match relative_mouse {
+                None => window.warp_mouse(coordinates.display_to_points(io.mouse_pos)),
+                Some(RelativeMouse::VirtualCursor) => self.virtual_mouse_pos = io.mouse_pos,
+                Some(RelativeMouse::Suspend) => {}
+            }
+        }
+
+        let mouse_position = match relative_mouse {
+            // The window gets no mouse motion while the OS drives a drag
+            None if self.os_drag.is_some() => coordinates.points_to_display(window.drop_position()),
+            // SDL only reports the warped position once the warp events are handled
+            None if io.want_set_mouse_pos => io.mouse_pos,
+            // The cursor imgui draws must not stay where the mouse left the window
+            None if io.mouse_draw_cursor && !window.has_mouse_focus() => NO_MOUSE_POS,
+            None => coordinates.points_to_display(window.mouse_position()),
+            Some(RelativeMouse::VirtualCursor) => self.virtual_mouse_pos,
+            Some(RelativeMouse::Suspend) => NO_MOUSE_POS,
+        };
 
         // Set display size and scale here, since SDL 2 doesn't have
         // any easy way to get the scale factor, and changes in said
         // scale factor
-        io.display_size = [window_size.0 as f32, window_size.1 as f32];
-        io.display_framebuffer_scale = [
-            (window_drawable_size.0 as f32) / (window_size.0 as f32),
-            (window_drawable_size.1 as f32) / (window_size.1 as f32),
-        ];
-        io.font_global_scale = content_scale;
+        let frame = FrameInput {
+            events: Vec::new(),
+            display_size: [window_size.0 as f32, window_size.1 as f32],
+            framebuffer_scale: coordinates.framebuffer_scale(),
+            content_scale: window.content_scale(),
+            delta_time: self.clock.delta_time(),
+            mouse_pos: mouse_position,
+        };
 
-        // Set mouse position if requested by imgui-rs
-        if io.want_set_mouse_pos {
-            let mouse_util = sdl.mouse();
-            mouse_util.warp_mouse_in_window(window, io.mouse_pos[0], io.mouse_pos[1]);
+        self.apply_frame(context, &frame);
+
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record_frame(frame);
         }
 
-        // Update mouse cursor position
-        io.mouse_pos = [mouse_state.x(), mouse_state.y()];
+        let io = context.io();
 
-        // Update mouse cursor icon if requested
-        if !io.config_flags.contains(ConfigFlags::NO_MOUSE_CURSOR_CHANGE) {
-            let mouse_util = sdl.mouse();
+        self.custom_cursor = CUSTOM_CURSOR.take();
 
-            match mouse_cursor {
-                Some(mouse_cursor) if !io.mouse_draw_cursor => {
-                    let cursor = Cursor::from_system(to_sdl_cursor(mouse_cursor)).unwrap();
-                    cursor.set();
+        // Update mouse cursor icon if requested, the application owns it in relative mode
+        if !relative_mode && !io.config_flags.contains(ConfigFlags::NO_MOUSE_CURSOR_CHANGE) {
+            match self.requested_cursor(context) {
+                Some(mouse_cursor) if !io.mouse_draw_cursor => window.set_cursor(Some(mouse_cursor)),
+                _ => window.set_cursor(None),
+            }
+        }
 
-                    mouse_util.show_cursor(true);
-                    self.cursor_instance = Some(cursor);
-                }
+        if let Some((position, line_height)) = TEXT_INPUT_AREA.take() {
+            let [x, y] = coordinates.display_to_points(position);
+            let [_, bottom] = coordinates.display_to_points([position[0], position[1] + line_height]);
+            window.set_text_input_area([x, y], bottom - y);
+        }
+    }
 
-                _ => {
-                    mouse_util.show_cursor(false);
-                    self.cursor_instance = None;
-                }
-            }
+    /// Submits the drag from the OS hovering the window (if any) to imgui, see `dragdrop`.
+    ///
+    /// Call it right after `imgui::Context::new_frame`, `ImGuiSdl3` does.
+    pub fn submit_os_drag(&mut self, ui: &imgui::Ui) {
+        if self.os_drag.as_mut().is_some_and(|os_drag| !os_drag.submit(ui)) {
+            self.os_drag = None;
         }
     }
+
+    /// Whether a drag from the OS hovers the window.
+    pub fn is_os_drag_active(&self) -> bool {
+        self.os_drag.is_some()
+    }
+
+    /// Frame preparation callback replaying a recorded frame, instead of `handle_event`
+    /// and `prepare_frame`.
+    ///
+    /// The window, mouse and clock are left untouched.
+    pub fn replay_frame(&mut self, context: &mut Context, frame: &FrameInput) {
+        let io = context.io_mut();
+        for event in &frame.events {
+            self.apply_input(io, event);
+        }
+
+        self.apply_frame(context, frame);
+    }
+}
+
+/// Clipboard backend giving imgui-rs access to the system clipboard through SDL.
+///
+/// # Example
+/// ```rust,no_run
+/// # let video_subsystem: sdl3::VideoSubsystem = unimplemented!();
+/// # let mut imgui = imgui::Context::create();
+/// use imgui_sdl3::platform::SdlClipboard;
+/// imgui.set_clipboard_backend(SdlClipboard::new(video_subsystem.clipboard()));
+/// ```
+pub struct SdlClipboard {
+    clipboard: ClipboardUtil,
+}
+
+impl SdlClipboard {
+    pub fn new(clipboard: ClipboardUtil) -> Self {
+        Self { clipboard }
+    }
+}
+
+impl imgui::ClipboardBackend for SdlClipboard {
+    fn get(&mut self) -> Option<String> {
+        if !self.clipboard.has_clipboard_text() {
+            return None;
+        }
+
+        self.clipboard.clipboard_text().ok()
+    }
+
+    fn set(&mut self, value: &str) {
+        // imgui-rs has no way to report the failure
+        let _ = self.clipboard.set_clipboard_text(value);
+    }
 }
 
 /// Returns `true` if the provided event is associated with the provided window.
@@ -199,20 +484,165 @@ pub fn filter_event(window: &Window, event: &Event) -> bool {
 }
 
 impl Platform {
+    /// Tracks a drag from the OS.
+    ///
+    /// imgui drags need a held mouse button while the OS keeps the actual one: the button is
+    /// pressed and released here, out of the recorded inputs, so that replays do the same.
+    fn apply_drop(&mut self, io: &mut Io, event: &DropEvent) {
+        let os_drag = self.os_drag.get_or_insert_with(|| {
+            InputEvent::MouseButton(MouseButton::Left, true).apply(io);
+            OsDrag::default()
+        });
+
+        match event {
+            DropEvent::Begin => {}
+            DropEvent::File(path) => os_drag.add_file(path),
+            DropEvent::Text(text) => os_drag.add_text(text),
+            DropEvent::Complete => {
+                if !os_drag.complete() {
+                    // Nothing was dropped, e.g. the drag left the window
+                    self.os_drag = None;
+                    InputEvent::MouseButton(MouseButton::Left, false).apply(io);
+                }
+            }
+        }
+    }
+
+    /// Switches imgui's mouse when the application enables or disables relative mouse mode.
+    fn toggle_relative_mode<W: PlatformWindow + ?Sized>(&mut self, context: &mut Context, window: &W, enabled: bool) {
+        self.relative_mode = enabled;
+        let io = context.io_mut();
+
+        if enabled {
+            match self.relative_mouse {
+                RelativeMouse::Suspend => {
+                    // Buttons released while suspended would otherwise stay held
+                    for button in MouseButton::VARIANTS {
+                        if io[button] {
+                            self.send_input(io, InputEvent::MouseButton(button, false));
+                        }
+                    }
+                }
+                RelativeMouse::VirtualCursor => {
+                    // The system cursor is hidden in relative mode, imgui draws the virtual one
+                    self.virtual_mouse_pos = window.mouse_position();
+                    self.draws_virtual_cursor = !io.mouse_draw_cursor;
+                    io.mouse_draw_cursor = true;
+                }
+            }
+        } else if self.draws_virtual_cursor {
+            io.mouse_draw_cursor = false;
+            self.draws_virtual_cursor = false;
+        }
+    }
+
+    /// The cursor imgui code requested for the last frame, `None` if hidden.
+    fn requested_cursor(&self, context: &Context) -> Option<CursorShape<'_>> {
+        // `window::set_custom_cursor` leaves imgui at the arrow, other cursors set after it win
+        let cursor = context.mouse_cursor()?;
+        match self
+            .custom_cursor
+            .and_then(|id| Some((id, self.custom_cursors.get(&id)?)))
+        {
+            Some((id, image)) if cursor == imgui::MouseCursor::Arrow => Some(CursorShape::Custom(id, image)),
+            _ => Some(CursorShape::System(cursor)),
+        }
+    }
+
+    /// Feeds an input to imgui, and records it while recording.
+    fn send_input(&mut self, io: &mut Io, input: InputEvent) {
+        self.apply_input(io, &input);
+
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record_event(input);
+        }
+    }
+
+    fn apply_input(&mut self, io: &mut Io, input: &InputEvent) {
+        match input {
+            InputEvent::Drop(event) => self.apply_drop(io, event),
+            _ => input.apply(io),
+        }
+    }
+
     fn handle_mouse_button(&mut self, io: &mut Io, button: &sdl3::mouse::MouseButton, pressed: bool) {
-        match button {
-            sdl3::mouse::MouseButton::Left => io.add_mouse_button_event(imgui::MouseButton::Left, pressed),
-            sdl3::mouse::MouseButton::Right => io.add_mouse_button_event(imgui::MouseButton::Right, pressed),
-            sdl3::mouse::MouseButton::Middle => io.add_mouse_button_event(imgui::MouseButton::Middle, pressed),
-            sdl3::mouse::MouseButton::X1 => io.add_mouse_button_event(imgui::MouseButton::Extra1, pressed),
-            sdl3::mouse::MouseButton::X2 => io.add_mouse_button_event(imgui::MouseButton::Extra2, pressed),
-            _ => {}
+        let button = match button {
+            sdl3::mouse::MouseButton::Left => imgui::MouseButton::Left,
+            sdl3::mouse::MouseButton::Right => imgui::MouseButton::Right,
+            sdl3::mouse::MouseButton::Middle => imgui::MouseButton::Middle,
+            sdl3::mouse::MouseButton::X1 => imgui::MouseButton::Extra1,
+            sdl3::mouse::MouseButton::X2 => imgui::MouseButton::Extra2,
+            _ => return,
+        };
+
+        self.send_input(io, InputEvent::MouseButton(button, pressed));
+    }
+
+    /// Handle changes in the key states.
+    fn handle_key(&mut self, io: &mut Io, key: &Scancode, pressed: bool) {
+        if let Some(key) = to_imgui_key(key) {
+            self.send_input(io, InputEvent::Key(key, pressed));
+        }
+    }
+
+    /// Handle changes in the key modifier states.
+    fn handle_key_modifier(&mut self, io: &mut Io, keymod: &Mod) {
+        // handle the actual modifiers:
+        let modifiers = [
+            (imgui::Key::ModShift, Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+            (imgui::Key::ModCtrl, Mod::LCTRLMOD | Mod::RCTRLMOD),
+            (imgui::Key::ModAlt, Mod::LALTMOD | Mod::RALTMOD),
+            (imgui::Key::ModSuper, Mod::LGUIMOD | Mod::RGUIMOD),
+        ];
+
+        for (key, mask) in modifiers {
+            self.send_input(io, InputEvent::Key(key, keymod.intersects(mask)));
         }
     }
+
+    /// Feeds the frame state to imgui.
+    fn apply_frame(&mut self, context: &mut Context, frame: &FrameInput) {
+        // Apply incremental scaling relative to the last known hi-dpi factor to
+        // avoid accumulating ScaleAllSizes every frame which can shrink sizes
+        // below allowed minima and trigger ImGui sanity checks.
+        let scale_factor = frame.content_scale / self.hidpi_factor;
+        if (scale_factor - 1.0).abs() > f32::EPSILON {
+            context.style_mut().scale_all_sizes(scale_factor);
+            self.hidpi_factor = frame.content_scale;
+        }
+
+        let io = context.io_mut();
+
+        // The drop was submitted on the previous frame, release the button held for it
+        if self.os_drag.as_ref().is_some_and(OsDrag::wants_release) {
+            InputEvent::MouseButton(MouseButton::Left, false).apply(io);
+        }
+
+        // Update delta time
+        io.update_delta_time(frame.delta_time);
+
+        io.display_size = frame.display_size;
+        io.display_framebuffer_scale = frame.framebuffer_scale;
+        io.font_global_scale = frame.content_scale;
+
+        // Update mouse cursor position
+        io.mouse_pos = frame.mouse_pos;
+    }
+}
+
+/// Stores the text input area imgui requests, `SetPlatformImeDataFn` is called while rendering.
+unsafe extern "C" fn set_platform_ime_data(
+    _viewport: *mut imgui::sys::ImGuiViewport,
+    data: *mut imgui::sys::ImGuiPlatformImeData,
+) {
+    let data = unsafe { &*data };
+    if data.WantVisible {
+        TEXT_INPUT_AREA.set(Some(([data.InputPos.x, data.InputPos.y], data.InputLineHeight)));
+    }
 }
 
-/// Handle changes in the key states.
-fn handle_key(io: &mut Io, key: &Scancode, pressed: bool) {
+/// Map a SDL scancode to an imgui key.
+fn to_imgui_key(key: &Scancode) -> Option<imgui::Key> {
     let igkey = match key {
         Scancode::A => imgui::Key::A,
         Scancode::B => imgui::Key::B,
@@ -321,33 +751,275 @@ fn handle_key(io: &mut Io, key: &Scancode, pressed: bool) {
         Scancode::RGui => imgui::Key::RightSuper,
         _ => {
             // Ignore unknown keys
-            return;
+            return None;
         }
     };
 
-    io.add_key_event(igkey, pressed);
+    Some(igkey)
 }
 
-/// Handle changes in the key modifier states.
-fn handle_key_modifier(io: &mut Io, keymod: &Mod) {
-    // handle the actual modifiers:
-    io.add_key_event(imgui::Key::ModShift, keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD));
-    io.add_key_event(imgui::Key::ModCtrl, keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD));
-    io.add_key_event(imgui::Key::ModAlt, keymod.intersects(Mod::LALTMOD | Mod::RALTMOD));
-    io.add_key_event(imgui::Key::ModSuper, keymod.intersects(Mod::LGUIMOD | Mod::RGUIMOD));
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        clock::FixedClock,
+        dragdrop::{DropPayload, accept_os_drop},
+        software::Image,
+        test_utils::imgui_lock,
+        window::{MockWindow, set_custom_cursor},
+    };
+    use imgui::MouseCursor;
+    use std::time::Duration;
+
+    fn context() -> Context {
+        let mut context = Context::create();
+        context.set_ini_filename(None);
+        context.fonts().build_rgba32_texture();
+        context
+    }
+
+    fn platform(context: &mut Context) -> Platform {
+        Platform::new(context).with_clock(FixedClock::new(Duration::from_millis(16)))
+    }
+
+    fn key_event(scancode: Scancode, keymod: Mod, down: bool) -> Event {
+        if down {
+            Event::KeyDown {
+                timestamp: 0,
+                window_id: 0,
+                keycode: None,
+                scancode: Some(scancode),
+                keymod,
+                repeat: false,
+                which: 0,
+                raw: 0,
+            }
+        } else {
+            Event::KeyUp {
+                timestamp: 0,
+                window_id: 0,
+                keycode: None,
+                scancode: Some(scancode),
+                keymod,
+                repeat: false,
+                which: 0,
+                raw: 0,
+            }
+        }
+    }
+
+    #[test]
+    fn display_size_and_scale() {
+        let _lock = imgui_lock();
+        let mut context = context();
+        let mut platform = platform(&mut context);
+        let mut window = MockWindow::new(400, 300).with_scale(2.0);
+        window.mouse_position = [10.0, 20.0];
+
+        platform.prepare_frame_with(&mut context, &mut window);
+
+        let io = context.io();
+        assert_eq!(io.display_size, [400.0, 300.0]);
+        assert_eq!(io.display_framebuffer_scale, [2.0, 2.0]);
+        assert_eq!(io.font_global_scale, 2.0);
+        assert_eq!(io.mouse_pos, [10.0, 20.0]);
+        assert_eq!(io.delta_time, 0.016);
+        assert!(window.warps.is_empty());
+    }
+
+    #[test]
+    fn mouse_without_focus() {
+        let _lock = imgui_lock();
+        let mut context = context();
+        let mut platform = platform(&mut context);
+        let mut window = MockWindow::new(400, 300);
+        window.mouse_position = [10.0, 20.0];
+        window.mouse_focus = false;
+
+        // SDL keeps reporting the last position, imgui only drops it when drawing the cursor
+        platform.prepare_frame_with(&mut context, &mut window);
+        assert_eq!(context.io().mouse_pos, [10.0, 20.0]);
+
+        context.io_mut().mouse_draw_cursor = true;
+        platform.prepare_frame_with(&mut context, &mut window);
+        assert_eq!(context.io().mouse_pos, NO_MOUSE_POS);
+    }
+
+    #[test]
+    fn warps_mouse() {
+        let _lock = imgui_lock();
+        let mut context = context();
+        let mut platform = platform(&mut context);
+        let mut window = MockWindow::new(400, 300).with_scale(2.0);
+        window.mouse_position = [10.0, 20.0];
 
-/// Map an imgui::MouseCursor to an equivalent sdl3::mouse::SystemCursor.
-fn to_sdl_cursor(cursor: MouseCursor) -> SystemCursor {
-    match cursor {
-        MouseCursor::Arrow => SystemCursor::Arrow,
-        MouseCursor::TextInput => SystemCursor::IBeam,
-        MouseCursor::ResizeAll => SystemCursor::SizeAll,
-        MouseCursor::ResizeNS => SystemCursor::SizeNS,
-        MouseCursor::ResizeEW => SystemCursor::SizeWE,
-        MouseCursor::ResizeNESW => SystemCursor::SizeNESW,
-        MouseCursor::ResizeNWSE => SystemCursor::SizeNWSE,
-        MouseCursor::Hand => SystemCursor::Hand,
-        MouseCursor::NotAllowed => SystemCursor::No,
+        let io = context.io_mut();
+        io.want_set_mouse_pos = true;
+        io.mouse_pos = [50.0, 60.0];
+        platform.prepare_frame_with(&mut context, &mut window);
+
+        // Warps are in logical pixels, and imgui keeps the position it asked for
+        assert_eq!(window.warps, [[50.0, 60.0]]);
+        assert_eq!(context.io().mouse_pos, [50.0, 60.0]);
+    }
+
+    #[test]
+    fn custom_cursor() {
+        let _lock = imgui_lock();
+        let mut context = context();
+        let mut platform = platform(&mut context);
+        let mut window = MockWindow::new(400, 300);
+        let eyedropper = platform
+            .register_cursor(CursorImage {
+                image: Image::new(16, 16),
+                hotspot: [0, 0],
+            })
+            .unwrap();
+
+        let mut frame = |context: &mut Context, platform: &mut Platform, ui_code: &dyn Fn(&imgui::Ui)| {
+            platform.prepare_frame_with(context, &mut window);
+            ui_code(context.new_frame());
+            context.render();
+            platform.prepare_frame_with(context, &mut window);
+            (window.cursor, window.custom_cursor)
+        };
+
+        let custom = |ui: &imgui::Ui| set_custom_cursor(ui, eyedropper);
+        assert_eq!(frame(&mut context, &mut platform, &custom), (None, Some(eyedropper)));
+        // imgui itself keeps a cursor it knows
+        assert_eq!(context.mouse_cursor(), Some(MouseCursor::Arrow));
+
+        // Only for the frames requesting it
+        assert_eq!(
+            frame(&mut context, &mut platform, &|_| {}),
+            (Some(MouseCursor::Arrow), None)
+        );
+
+        // A cursor set afterwards wins
+        let resize = |ui: &imgui::Ui| {
+            set_custom_cursor(ui, eyedropper);
+            ui.set_mouse_cursor(Some(MouseCursor::ResizeEW));
+        };
+        assert_eq!(
+            frame(&mut context, &mut platform, &resize),
+            (Some(MouseCursor::ResizeEW), None)
+        );
+
+        // imgui draws the arrow, the system cursor is hidden
+        context.io_mut().mouse_draw_cursor = true;
+        assert_eq!(frame(&mut context, &mut platform, &custom), (None, None));
+        context.io_mut().mouse_draw_cursor = false;
+
+        platform.unregister_cursor(eyedropper);
+        assert_eq!(
+            frame(&mut context, &mut platform, &custom),
+            (Some(MouseCursor::Arrow), None)
+        );
+    }
+
+    #[test]
+    fn key_events() {
+        let _lock = imgui_lock();
+        let mut context = context();
+        let mut platform = platform(&mut context);
+        let mut window = MockWindow::new(400, 300);
+
+        assert!(platform.handle_event(&mut context, &key_event(Scancode::A, Mod::LCTRLMOD, true)));
+        platform.prepare_frame_with(&mut context, &mut window);
+        let ui = context.new_frame();
+        assert!(ui.is_key_down(imgui::Key::A));
+        assert!(ui.io().key_ctrl);
+        assert!(!ui.io().key_shift);
+        context.render();
+
+        assert!(platform.handle_event(&mut context, &key_event(Scancode::A, Mod::NOMOD, false)));
+        platform.prepare_frame_with(&mut context, &mut window);
+        let ui = context.new_frame();
+        assert!(!ui.is_key_down(imgui::Key::A));
+        assert!(!ui.io().key_ctrl);
+        context.render();
+    }
+
+    /// Runs a frame with a drop target covering the mouse, returns what it accepted.
+    fn drop_target_frame(platform: &mut Platform, context: &mut Context) -> Option<DropPayload> {
+        let ui = context.new_frame();
+        platform.submit_os_drag(ui);
+
+        let mut dropped = None;
+        ui.window("Target")
+            .position([0.0, 0.0], imgui::Condition::Always)
+            .build(|| {
+                ui.button_with_size("Drop here", [200.0, 100.0]);
+                if let Some(target) = ui.drag_drop_target() {
+                    dropped = accept_os_drop(&target);
+                }
+            });
+        context.render();
+        dropped
+    }
+
+    #[test]
+    fn drop_is_replayed() {
+        let _lock = imgui_lock();
+        let file = Event::DropFile {
+            timestamp: 0,
+            window_id: 0,
+            filename: "/tmp/dropped.png".to_string(),
+        };
+        let expected = DropPayload::Files(vec!["/tmp/dropped.png".into()]);
+
+        let recording = {
+            let mut context = context();
+            let mut platform = platform(&mut context);
+            let mut window = MockWindow::new(400, 300);
+            window.mouse_position = [50.0, 60.0];
+            platform.start_recording();
+
+            let mut dropped = Vec::new();
+            for frame in 0..6 {
+                let event = match frame {
+                    1 => Some(Event::DropBegin {
+                        timestamp: 0,
+                        window_id: 0,
+                    }),
+                    2 => Some(file.clone()),
+                    3 => Some(Event::DropComplete {
+                        timestamp: 0,
+                        window_id: 0,
+                    }),
+                    _ => None,
+                };
+                if let Some(event) = event {
+                    assert!(platform.handle_event(&mut context, &event));
+                }
+                platform.prepare_frame_with(&mut context, &mut window);
+                dropped.extend(drop_target_frame(&mut platform, &mut context));
+            }
+            assert_eq!(dropped, std::slice::from_ref(&expected));
+            assert!(!platform.is_os_drag_active());
+            platform.stop_recording().unwrap()
+        };
+
+        // The mouse button held for the drag is not recorded, replays hold it again
+        let events: Vec<_> = recording.frames().iter().flat_map(|frame| &frame.events).collect();
+        assert_eq!(
+            events,
+            [
+                &InputEvent::Drop(DropEvent::Begin),
+                &InputEvent::Drop(DropEvent::File("/tmp/dropped.png".to_string())),
+                &InputEvent::Drop(DropEvent::Complete),
+            ]
+        );
+
+        let mut context = context();
+        let mut platform = platform(&mut context);
+        let mut dropped = Vec::new();
+        for frame in recording.frames() {
+            platform.replay_frame(&mut context, frame);
+            dropped.extend(drop_target_frame(&mut platform, &mut context));
+        }
+        assert_eq!(dropped, [expected]);
+        assert!(!platform.is_os_drag_active());
+        assert!(!context.io()[MouseButton::Left]);
     }
 }
@@ -0,0 +1,479 @@
+//! Input recording and deterministic replay.
+//!
+//! While recording, `Platform` stores the imgui inputs produced by every event passed to
+//! `Platform::handle_event`, along with what `Platform::prepare_frame` feeds imgui each
+//! frame (display size and scale, time step, mouse position). Replaying a `Recording`
+//! with `Platform::replay_frame` (or `ImGuiSdl3::replay_frame`) then produces the same
+//! imgui frames without any live SDL input.
+//!
+//! # Example
+//! ```rust,no_run
+//! # let mut imgui: imgui_sdl3::ImGuiSdl3 = unimplemented!();
+//! use imgui_sdl3::recording::Recording;
+//!
+//! // while running the application
+//! imgui.platform_mut().start_recording();
+//! // ...
+//! let recording = imgui.platform_mut().stop_recording().unwrap();
+//! recording.save("session.imrec").unwrap();
+//!
+//! // later, e.g. in a new build of the application
+//! let recording = Recording::load("session.imrec").unwrap();
+//! for frame in recording.frames() {
+//!     let ui = imgui.replay_frame(frame);
+//!     // ... build the UI and render it
+//! }
+//! ```
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, ErrorKind, Read, Write},
+    path::Path,
+    time::Duration,
+};
+
+use imgui::{Io, Key, MouseButton};
+
+use crate::dragdrop::DropEvent;
+
+/// Identifies recording files, followed by the format version.
+const MAGIC: &[u8; 4] = b"IMRC";
+const VERSION: u8 = 1;
+
+/// Modifier keys, which `Key::VARIANTS` does not list.
+const MOD_KEYS: [Key; 5] = [
+    Key::ModCtrl,
+    Key::ModShift,
+    Key::ModAlt,
+    Key::ModSuper,
+    Key::ModShortcut,
+];
+
+/// An input fed to imgui.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputEvent {
+    /// Mouse position in logical pixels.
+    MousePos([f32; 2]),
+    MouseButton(MouseButton, bool),
+    /// Horizontal and vertical wheel movement.
+    MouseWheel([f32; 2]),
+    Key(Key, bool),
+    Text(String),
+    /// A drag from the OS, tracked by `Platform`.
+    Drop(DropEvent),
+}
+
+impl InputEvent {
+    /// Queues the input in imgui, it is processed on the next frame.
+    pub(crate) fn apply(&self, io: &mut Io) {
+        match self {
+            InputEvent::MousePos(pos) => io.add_mouse_pos_event(*pos),
+            InputEvent::MouseButton(button, down) => io.add_mouse_button_event(*button, *down),
+            InputEvent::MouseWheel(wheel) => io.add_mouse_wheel_event(*wheel),
+            InputEvent::Key(key, down) => io.add_key_event(*key, *down),
+            InputEvent::Text(text) => text.chars().for_each(|c| io.add_input_character(c)),
+            // Tracked by `Platform`, which also holds the mouse button imgui drags need
+            InputEvent::Drop(_) => {}
+        }
+    }
+}
+
+/// What imgui was fed for one frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameInput {
+    /// Inputs handled since the previous frame, in order.
+    pub events: Vec<InputEvent>,
+    /// Window size in logical pixels.
+    pub display_size: [f32; 2],
+    pub framebuffer_scale: [f32; 2],
+    /// Content (DPI) scale of the display.
+    pub content_scale: f32,
+    pub delta_time: Duration,
+    /// Mouse position in logical pixels.
+    pub mouse_pos: [f32; 2],
+}
+
+/// Frames recorded by `Platform::start_recording`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Recording {
+    frames: Vec<FrameInput>,
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn frames(&self) -> &[FrameInput] {
+        &self.frames
+    }
+
+    pub fn push_frame(&mut self, frame: FrameInput) {
+        self.frames.push(frame);
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Writes the recording to a file, see `write_to`.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Reads a recording written by `save`.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Writes the recording in a compact little endian binary format.
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        write_u32(&mut writer, self.frames.len() as u32)?;
+
+        for frame in &self.frames {
+            write_f32s(&mut writer, &frame.display_size)?;
+            write_f32s(&mut writer, &frame.framebuffer_scale)?;
+            write_f32s(&mut writer, &[frame.content_scale])?;
+            writer.write_all(
+                &u64::try_from(frame.delta_time.as_nanos())
+                    .unwrap_or(u64::MAX)
+                    .to_le_bytes(),
+            )?;
+            write_f32s(&mut writer, &frame.mouse_pos)?;
+
+            write_u32(&mut writer, frame.events.len() as u32)?;
+            for event in &frame.events {
+                write_event(&mut writer, event)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads a recording written by `write_to`.
+    pub fn read_from(mut reader: impl Read) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not an input recording"));
+        }
+        let [version] = read_array(&mut reader)?;
+        if version != VERSION {
+            return Err(invalid_data(format!("unsupported recording version {version}")));
+        }
+
+        let frame_count = read_u32(&mut reader)?;
+        let mut frames = Vec::new();
+
+        for _ in 0..frame_count {
+            let display_size = read_f32s(&mut reader)?;
+            let framebuffer_scale = read_f32s(&mut reader)?;
+            let [content_scale] = read_f32s(&mut reader)?;
+            let delta_time = Duration::from_nanos(u64::from_le_bytes(read_array(&mut reader)?));
+            let mouse_pos = read_f32s(&mut reader)?;
+
+            let event_count = read_u32(&mut reader)?;
+            let events = (0..event_count)
+                .map(|_| read_event(&mut reader))
+                .collect::<io::Result<_>>()?;
+
+            frames.push(FrameInput {
+                events,
+                display_size,
+                framebuffer_scale,
+                content_scale,
+                delta_time,
+                mouse_pos,
+            });
+        }
+
+        Ok(Self { frames })
+    }
+}
+
+/// Plays a recording back one frame at a time.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    recording: Recording,
+    next_frame: usize,
+}
+
+impl Replay {
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            recording,
+            next_frame: 0,
+        }
+    }
+
+    /// Returns the next frame to feed to `Platform::replay_frame`, `None` once finished.
+    pub fn next_frame(&mut self) -> Option<&FrameInput> {
+        let frame = self.recording.frames.get(self.next_frame)?;
+        self.next_frame += 1;
+        Some(frame)
+    }
+
+    /// Whether every frame was played.
+    pub fn is_finished(&self) -> bool {
+        self.next_frame >= self.recording.frames.len()
+    }
+
+    /// Starts playing from the first frame again.
+    pub fn rewind(&mut self) {
+        self.next_frame = 0;
+    }
+}
+
+/// Records frames while `Platform` handles events.
+pub(crate) struct Recorder {
+    recording: Recording,
+    /// Inputs handled since the previous frame.
+    events: Vec<InputEvent>,
+}
+
+impl Recorder {
+    pub(crate) fn new() -> Self {
+        Self {
+            recording: Recording::new(),
+            events: Vec::new(),
+        }
+    }
+
+    pub(crate) fn record_event(&mut self, event: InputEvent) {
+        self.events.push(event);
+    }
+
+    /// Closes the current frame, with the inputs handled since the previous one.
+    pub(crate) fn record_frame(&mut self, mut frame: FrameInput) {
+        frame.events = std::mem::take(&mut self.events);
+        self.recording.push_frame(frame);
+    }
+
+    pub(crate) fn finish(self) -> Recording {
+        self.recording
+    }
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message.into())
+}
+
+fn write_u32(writer: &mut impl Write, value: u32) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_f32s(writer: &mut impl Write, values: &[f32]) -> io::Result<()> {
+    values
+        .iter()
+        .try_for_each(|value| writer.write_all(&value.to_le_bytes()))
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    Ok(u32::from_le_bytes(read_array(reader)?))
+}
+
+fn read_f32s<const N: usize>(reader: &mut impl Read) -> io::Result<[f32; N]> {
+    let mut values = [0.0; N];
+    for value in &mut values {
+        *value = f32::from_le_bytes(read_array(reader)?);
+    }
+    Ok(values)
+}
+
+fn write_event(writer: &mut impl Write, event: &InputEvent) -> io::Result<()> {
+    match event {
+        InputEvent::MousePos(pos) => {
+            writer.write_all(&[0])?;
+            write_f32s(writer, pos)
+        }
+        InputEvent::MouseButton(button, down) => {
+            let index = MouseButton::VARIANTS.iter().position(|b| b == button).unwrap();
+            writer.write_all(&[1, index as u8, *down as u8])
+        }
+        InputEvent::MouseWheel(wheel) => {
+            writer.write_all(&[2])?;
+            write_f32s(writer, wheel)
+        }
+        InputEvent::Key(key, down) => {
+            writer.write_all(&[3])?;
+            write_u32(writer, *key as u32)?;
+            writer.write_all(&[*down as u8])
+        }
+        InputEvent::Text(text) => {
+            writer.write_all(&[4])?;
+            write_string(writer, text)
+        }
+        InputEvent::Drop(DropEvent::Begin) => writer.write_all(&[5, 0]),
+        InputEvent::Drop(DropEvent::File(path)) => {
+            writer.write_all(&[5, 1])?;
+            write_string(writer, path)
+        }
+        InputEvent::Drop(DropEvent::Text(text)) => {
+            writer.write_all(&[5, 2])?;
+            write_string(writer, text)
+        }
+        InputEvent::Drop(DropEvent::Complete) => writer.write_all(&[5, 3]),
+    }
+}
+
+fn read_event(reader: &mut impl Read) -> io::Result<InputEvent> {
+    let [tag] = read_array(reader)?;
+
+    match tag {
+        0 => Ok(InputEvent::MousePos(read_f32s(reader)?)),
+        1 => {
+            let [index, down] = read_array(reader)?;
+            let button = MouseButton::VARIANTS
+                .get(index as usize)
+                .ok_or_else(|| invalid_data(format!("unknown mouse button {index}")))?;
+            Ok(InputEvent::MouseButton(*button, down != 0))
+        }
+        2 => Ok(InputEvent::MouseWheel(read_f32s(reader)?)),
+        3 => {
+            let raw = read_u32(reader)?;
+            let [down] = read_array(reader)?;
+            let key = Key::VARIANTS
+                .iter()
+                .chain(&MOD_KEYS)
+                .find(|key| **key as u32 == raw)
+                .ok_or_else(|| invalid_data(format!("unknown key {raw}")))?;
+            Ok(InputEvent::Key(*key, down != 0))
+        }
+        4 => Ok(InputEvent::Text(read_string(reader)?)),
+        5 => {
+            let [kind] = read_array(reader)?;
+            let event = match kind {
+                0 => DropEvent::Begin,
+                1 => DropEvent::File(read_string(reader)?),
+                2 => DropEvent::Text(read_string(reader)?),
+                3 => DropEvent::Complete,
+                _ => return Err(invalid_data(format!("unknown drop event {kind}"))),
+            };
+            Ok(InputEvent::Drop(event))
+        }
+        _ => Err(invalid_data(format!("unknown event tag {tag}"))),
+    }
+}
+
+fn write_string(writer: &mut impl Write, string: &str) -> io::Result<()> {
+    write_u32(writer, string.len() as u32)?;
+    writer.write_all(string.as_bytes())
+}
+
+fn read_string(reader: &mut impl Read) -> io::Result<String> {
+    // The length is not trusted to allocate, a corrupt file may claim gigabytes
+    let len = read_u32(reader)?;
+    let mut bytes = Vec::new();
+    reader.by_ref().take(len.into()).read_to_end(&mut bytes)?;
+    if bytes.len() != len as usize {
+        return Err(io::Error::new(ErrorKind::UnexpectedEof, "truncated string"));
+    }
+    String::from_utf8(bytes).map_err(|e| invalid_data(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(events: Vec<InputEvent>) -> FrameInput {
+        FrameInput {
+            events,
+            display_size: [1280.0, 720.0],
+            framebuffer_scale: [2.0, 2.0],
+            content_scale: 1.5,
+            delta_time: Duration::from_nanos(16_666_667),
+            mouse_pos: [-f32::MAX, 12.5],
+        }
+    }
+
+    fn write(recording: &Recording) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        recording.write_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut events = vec![
+            InputEvent::MousePos([10.0, 20.5]),
+            InputEvent::MouseWheel([-1.0, 0.5]),
+            InputEvent::Text("héllo 🦀".into()),
+            InputEvent::Text(String::new()),
+            InputEvent::Drop(DropEvent::Begin),
+            InputEvent::Drop(DropEvent::File("/tmp/a b.png".into())),
+            InputEvent::Drop(DropEvent::Text("dropped 🦀".into())),
+            InputEvent::Drop(DropEvent::Complete),
+        ];
+        events.extend(
+            MouseButton::VARIANTS
+                .iter()
+                .map(|&button| InputEvent::MouseButton(button, true)),
+        );
+        events.extend(Key::VARIANTS.iter().map(|&key| InputEvent::Key(key, false)));
+        events.extend(MOD_KEYS.iter().map(|&key| InputEvent::Key(key, true)));
+
+        let mut recording = Recording::new();
+        recording.push_frame(frame(events));
+        recording.push_frame(frame(Vec::new()));
+
+        let read = Recording::read_from(write(&recording).as_slice()).unwrap();
+        assert_eq!(read, recording);
+    }
+
+    #[test]
+    fn bad_magic() {
+        let mut bytes = write(&Recording::new());
+        bytes[0] = b'X';
+
+        let error = Recording::read_from(bytes.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn bad_version() {
+        let mut bytes = write(&Recording::new());
+        bytes[MAGIC.len()] = VERSION + 1;
+
+        let error = Recording::read_from(bytes.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(error.to_string().contains("version"));
+    }
+
+    #[test]
+    fn truncated_text() {
+        let mut recording = Recording::new();
+        recording.push_frame(frame(vec![InputEvent::Text("truncated".into())]));
+        let bytes = write(&recording);
+
+        let error = Recording::read_from(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn huge_text_length() {
+        let mut recording = Recording::new();
+        recording.push_frame(frame(vec![InputEvent::Text(String::new())]));
+        let mut bytes = write(&recording);
+
+        // The text length is the last field, claim 4 GB that are not there
+        let len = bytes.len();
+        bytes[len - 4..].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        let error = Recording::read_from(bytes.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    }
+}
@@ -1,24 +1,420 @@
 use std::{
     error::Error,
     mem::{offset_of, size_of},
+    ptr::NonNull,
 };
 
-use imgui::{DrawCmdParams, DrawIdx, DrawVert, TextureId, internal::RawWrapper};
-use sdl3::{gpu::*, rect::Rect, video::Window};
+use imgui::{BackendFlags, DrawCmdParams, DrawData, DrawIdx, DrawVert, TextureId, internal::RawWrapper};
+use sdl3::{
+    gpu::*,
+    rect::Rect,
+    sys::gpu::{
+        SDL_BindGPUGraphicsPipeline, SDL_CreateGPUGraphicsPipeline, SDL_GPU_BLENDFACTOR_ONE,
+        SDL_GPU_BLENDFACTOR_ONE_MINUS_SRC_ALPHA, SDL_GPU_BLENDFACTOR_SRC_ALPHA, SDL_GPU_BLENDOP_ADD,
+        SDL_GPU_CULLMODE_NONE, SDL_GPU_FILLMODE_FILL, SDL_GPU_FRONTFACE_CLOCKWISE, SDL_GPU_PRIMITIVETYPE_TRIANGLELIST,
+        SDL_GPU_VERTEXELEMENTFORMAT_FLOAT2, SDL_GPU_VERTEXELEMENTFORMAT_UBYTE4_NORM, SDL_GPU_VERTEXINPUTRATE_VERTEX,
+        SDL_GPUBlendFactor, SDL_GPUColorTargetBlendState, SDL_GPUColorTargetDescription, SDL_GPUGraphicsPipeline,
+        SDL_GPUGraphicsPipelineCreateInfo, SDL_GPUGraphicsPipelineTargetInfo, SDL_GPUMultisampleState,
+        SDL_GPURasterizerState, SDL_GPUSampleCount, SDL_GPUTextureFormat, SDL_GPUTextureSupportsSampleCount,
+        SDL_GPUVertexAttribute, SDL_GPUVertexBufferDescription, SDL_GPUVertexInputState,
+        SDL_ReleaseGPUGraphicsPipeline,
+    },
+    video::Window,
+};
 
-use crate::utils::{create_buffer_with_data, create_texture};
+use crate::{
+    backend::RendererBackend,
+    coordinates::Coordinates,
+    utils::{PixelFormat, create_buffer_with_data, create_sampler, create_texture},
+};
 
 /// Renderer backend for imgui using SDL3 GPU.
 ///
 /// This renderer performs the following tasks:
 ///
-/// * Initializes a pipeline with blending suitable for ImGui
+/// * Initializes pipelines with blending suitable for ImGui
 /// * Uploads the ImGui font atlas as a GPU texture
 /// * Creates GPU buffers every frame for ImGui vertex/index data
 /// * Issues draw calls using ImGui's draw list
+/// * Encodes ImGui's sRGB colors for the target color space (SDR, linear or HDR)
 pub struct Renderer {
-    pipeline: GraphicsPipeline,
-    textures: Vec<(Texture<'static>, Sampler)>,
+    /// One pipeline per fragment shader variant, indexed by `ShaderVariant`.
+    pipelines: Vec<Pipeline>,
+    /// Shaders are kept around to rebuild the pipelines when the target format changes.
+    vertex_shader: Shader,
+    fragment_shaders: Vec<Shader>,
+    target_format: TextureFormat,
+    /// Samples per pixel of the render targets.
+    sample_count: SampleCount,
+    /// Registered textures indexed by `TextureId`, the font atlas is always 0.
+    textures: Vec<Option<RegisteredTexture>>,
+    /// Textures pushed for the current frame only, released after rendering.
+    frame_textures: Vec<TextureId>,
+    /// Alpha mode of the colors written to the render target.
+    alpha_mode: AlphaMode,
+    /// Color space of the render target.
+    color_space: SwapchainComposition,
+    /// Linear layer the UI is blended in before being PQ-encoded, on HDR10 targets only.
+    layer: Option<LinearLayer>,
+    /// Brightness of the UI on HDR targets, in nits.
+    sdr_white_level: f32,
+    /// Settings of the default sampler, kept to recreate it with the font atlas.
+    filter: Filter,
+    address_mode: SamplerAddressMode,
+}
+
+/// Where the SDL GPU renderer draws a frame, see `RendererBackend::Target`.
+pub struct GpuTarget<'a> {
+    pub device: &'a Device,
+    /// Command buffer the render pass is recorded into.
+    pub command_buffer: &'a mut CommandBuffer,
+    pub color_targets: &'a [ColorTargetInfo],
+}
+
+/// Reference white level for SDR content on HDR displays, in nits (ITU-R BT.2408).
+pub const DEFAULT_SDR_WHITE_LEVEL: f32 = 203.0;
+
+/// How color channels relate to the alpha channel.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AlphaMode {
+    /// Colors are independent of alpha, as produced by most image decoders.
+    #[default]
+    Straight,
+    /// Colors are already multiplied by alpha, as expected when compositing layers.
+    Premultiplied,
+}
+
+/// How the color values returned by the sampler are encoded.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ColorEncoding {
+    /// sRGB-encoded values, as stored in UNORM textures.
+    #[default]
+    Srgb,
+    /// Linear values, from sRGB textures decoded by the sampler or float textures.
+    Linear,
+}
+
+/// Settings a `Renderer` is created with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RendererConfig {
+    /// Format of the render targets, the window swapchain format by default.
+    pub target_format: TextureFormat,
+    /// Filter of the default sampler, used for the font atlas and `default_sampler`.
+    pub filter: Filter,
+    /// Address mode of the default sampler on all axes.
+    pub address_mode: SamplerAddressMode,
+    /// Color space ImGui colors are encoded for.
+    ///
+    /// `SdrLinear` for sRGB formats (which encode in hardware) and `Sdr` otherwise by default,
+    /// HDR color spaces must be requested explicitly.
+    pub color_space: SwapchainComposition,
+    /// Samples per pixel of the render targets, single-sampled by default.
+    pub sample_count: SampleCount,
+}
+
+impl RendererConfig {
+    pub fn new(target_format: TextureFormat) -> Self {
+        Self {
+            target_format,
+            filter: Filter::Linear,
+            address_mode: SamplerAddressMode::ClampToEdge,
+            color_space: match target_format {
+                TextureFormat::B8g8r8a8UnormSrgb | TextureFormat::R8g8b8a8UnormSrgb => SwapchainComposition::SdrLinear,
+                _ => SwapchainComposition::Sdr,
+            },
+            sample_count: SampleCount::NoMultiSampling,
+        }
+    }
+
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_address_mode(mut self, address_mode: SamplerAddressMode) -> Self {
+        self.address_mode = address_mode;
+        self
+    }
+
+    pub fn with_color_space(mut self, color_space: SwapchainComposition) -> Self {
+        self.color_space = color_space;
+        self
+    }
+
+    /// Renders into multisampled targets, which must support the sample count.
+    pub fn with_sample_count(mut self, sample_count: SampleCount) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+}
+
+/// Options describing how a registered texture is sampled.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TextureOptions {
+    /// Alpha mode of the texture data.
+    pub alpha_mode: AlphaMode,
+    /// Encoding of the color values returned by the sampler.
+    pub encoding: ColorEncoding,
+    /// How the texture is inspected (layer, mip level, channels, tone mapping).
+    pub view: ImageView,
+    /// Pixel format of the texture, read when it is registered to size `Renderer::update_texture` data.
+    pub format: PixelFormat,
+}
+
+impl TextureOptions {
+    /// Returns the default options for a texture created with the given format.
+    pub fn for_format(format: PixelFormat) -> Self {
+        Self {
+            encoding: if format.is_linear() {
+                ColorEncoding::Linear
+            } else {
+                ColorEncoding::Srgb
+            },
+            format,
+            ..Self::default()
+        }
+    }
+}
+
+/// Source of an output channel in a `Swizzle`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    R,
+    G,
+    B,
+    A,
+    Zero,
+    One,
+}
+
+/// Source channels of the red, green, blue and alpha outputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Swizzle(pub [Channel; 4]);
+
+impl Swizzle {
+    pub const IDENTITY: Self = Self([Channel::R, Channel::G, Channel::B, Channel::A]);
+
+    /// Shows a single channel as an opaque grayscale image.
+    pub fn isolate(channel: Channel) -> Self {
+        Self([channel, channel, channel, Channel::One])
+    }
+}
+
+impl Default for Swizzle {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// Curve mapping linear, unbounded values of float textures to displayable ones.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ToneMapping {
+    /// The texture already holds displayable sRGB-encoded values.
+    #[default]
+    None,
+    /// Linear values are clamped to `[0, 1]`.
+    Clamp,
+    Reinhard,
+    /// Narkowicz's fit of the ACES filmic curve.
+    Aces,
+}
+
+/// View options to inspect textures that are not plain color images, such as shadow
+/// maps, normal buffers, HDR render targets or texture arrays.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageView {
+    /// Layer to sample, the texture must then be a 2D array.
+    pub layer: Option<u32>,
+    /// Mip level to sample, instead of letting the sampler pick one.
+    pub mip_level: Option<u32>,
+    pub swizzle: Swizzle,
+    /// Near and far planes used to linearize a (non reversed) depth texture.
+    pub depth_range: Option<[f32; 2]>,
+    /// Exposure in stops, applied before tone mapping.
+    pub exposure: f32,
+    pub tone_mapping: ToneMapping,
+}
+
+impl Default for ImageView {
+    fn default() -> Self {
+        Self {
+            layer: None,
+            mip_level: None,
+            swizzle: Swizzle::IDENTITY,
+            depth_range: None,
+            exposure: 0.0,
+            tone_mapping: ToneMapping::None,
+        }
+    }
+}
+
+impl ImageView {
+    /// Returns the fragment shader variant able to sample a texture with this view.
+    fn variant(&self) -> ShaderVariant {
+        if self.layer.is_some() {
+            ShaderVariant::ViewArray
+        } else if *self != Self::default() {
+            ShaderVariant::View
+        } else {
+            ShaderVariant::Default
+        }
+    }
+}
+
+/// Fragment shader variants, see `SHADER_VARIANTS` in `build.rs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ShaderVariant {
+    Default,
+    /// Applies an `ImageView` to a 2D texture.
+    View,
+    /// Applies an `ImageView` to a 2D array texture.
+    ViewArray,
+}
+
+/// A texture registered with the renderer, along with how to sample it.
+struct RegisteredTexture {
+    texture: Texture<'static>,
+    sampler: Sampler,
+    options: TextureOptions,
+    /// Format of the texture, which does not change with its options.
+    format: PixelFormat,
+    /// Upload buffer reused by `Renderer::update_texture`, created on first update.
+    staging: Option<TransferBuffer>,
+}
+
+/// Fragment shader uniforms, laid out to match the `Output` block in `imgui.frag`.
+#[repr(C)]
+struct OutputUniforms {
+    color_space: u32,
+    sdr_white_level: f32,
+    flags: u32,
+    _padding: u32,
+}
+
+const OUTPUT_FLAG_LINEARIZE_ALPHA: u32 = 1 << 0;
+const OUTPUT_FLAG_PREMULTIPLIED_TEXTURE: u32 = 1 << 1;
+const OUTPUT_FLAG_PREMULTIPLIED_OUTPUT: u32 = 1 << 2;
+const OUTPUT_FLAG_LINEAR_TEXTURE: u32 = 1 << 3;
+
+/// Color space value of the `Output` block for the HDR10 linear layer: premultiplied
+/// linear BT.2020 colors, 1.0 being 10000 nits.
+const COLOR_SPACE_HDR10_LAYER: u32 = 4;
+
+/// Format of the linear layer HDR10 targets are drawn through.
+const LAYER_FORMAT: TextureFormat = TextureFormat::R16g16b16a16Float;
+
+/// Intermediate target the UI is blended into on HDR10 targets.
+///
+/// PQ values are far from linear, blending them directly darkens anti-aliased edges and
+/// translucent windows, so the UI is blended on linear colors then PQ-encoded at once.
+struct LinearLayer {
+    /// Draws the layer onto the render target, encoding it to PQ.
+    composite_pipeline: Pipeline,
+    sampler: Sampler,
+    /// Created on first use, and again when the render target size changes.
+    texture: Option<Texture<'static>>,
+}
+
+impl LinearLayer {
+    fn new(device: &Device, target_format: TextureFormat, sample_count: SampleCount) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            composite_pipeline: create_composite_pipeline(device, target_format, sample_count)?,
+            sampler: create_sampler(device, Filter::Nearest)?,
+            texture: None,
+        })
+    }
+
+    /// Returns the layer texture, sized for a render target of the given size.
+    fn texture(&mut self, device: &Device, width: u32, height: u32) -> Result<&Texture<'static>, Box<dyn Error>> {
+        let resize = self
+            .texture
+            .as_ref()
+            .is_none_or(|texture| texture.width() != width || texture.height() != height);
+        if resize {
+            self.texture = Some(
+                device.create_texture(
+                    TextureCreateInfo::new()
+                        .with_format(LAYER_FORMAT)
+                        .with_type(TextureType::_2D)
+                        .with_width(width.max(1))
+                        .with_height(height.max(1))
+                        .with_layer_count_or_depth(1)
+                        .with_num_levels(1)
+                        .with_usage(TextureUsage::COLOR_TARGET | TextureUsage::SAMPLER),
+                )?,
+            );
+        }
+
+        Ok(self.texture.as_ref().unwrap())
+    }
+
+    /// Encodes the layer onto the render targets, in a render pass of its own.
+    fn composite(
+        &self,
+        device: &Device,
+        command_buffer: &CommandBuffer,
+        color_targets: &[ColorTargetInfo],
+        width: f32,
+        height: f32,
+    ) -> Result<(), Box<dyn Error>> {
+        let Some(texture) = &self.texture else {
+            return Ok(());
+        };
+
+        let render_pass = device.begin_render_pass(command_buffer, color_targets, None)?;
+        self.composite_pipeline.bind(&render_pass);
+        device.set_viewport(&render_pass, Viewport::new(0.0, 0.0, width, height, 0.0, 1.0));
+        render_pass.bind_fragment_samplers(
+            0,
+            &[TextureSamplerBinding::new()
+                .with_texture(texture)
+                .with_sampler(&self.sampler)],
+        );
+        // A single triangle covering the target
+        render_pass.draw_primitives(3, 1, 0, 0);
+        device.end_render_pass(render_pass);
+
+        Ok(())
+    }
+}
+
+/// Fragment shader uniforms, laid out to match the `View` block in `imgui.frag`.
+#[repr(C)]
+struct ViewUniforms {
+    swizzle: [u32; 4],
+    layer: f32,
+    mip_level: f32,
+    exposure: f32,
+    tone_mapping: u32,
+    depth_range: [f32; 2],
+    flags: u32,
+    _padding: u32,
+}
+
+const VIEW_FLAG_MIP_LEVEL: u32 = 1 << 0;
+const VIEW_FLAG_LINEARIZE_DEPTH: u32 = 1 << 1;
+
+impl From<&ImageView> for ViewUniforms {
+    fn from(view: &ImageView) -> Self {
+        let mut flags = 0;
+        if view.mip_level.is_some() {
+            flags |= VIEW_FLAG_MIP_LEVEL;
+        }
+        if view.depth_range.is_some() {
+            flags |= VIEW_FLAG_LINEARIZE_DEPTH;
+        }
+
+        Self {
+            swizzle: view.swizzle.0.map(|channel| channel as u32),
+            layer: view.layer.unwrap_or(0) as f32,
+            mip_level: view.mip_level.unwrap_or(0) as f32,
+            exposure: view.exposure,
+            tone_mapping: view.tone_mapping as u32,
+            depth_range: view.depth_range.unwrap_or([0.0, 1.0]),
+            flags,
+            _padding: 0,
+        }
+    }
 }
 
 impl Renderer {
@@ -26,113 +422,376 @@ impl Renderer {
     ///
     /// This function builds a graphics pipeline from SPIR-V vertex/fragment shaders,
     /// configures the vertex input state to match `DrawVert`, and uploads the ImGui font atlas.
+    ///
+    /// The color space is deduced from the window swapchain format.
     pub fn new(device: &Device, window: &Window, imgui_context: &mut imgui::Context) -> Result<Self, Box<dyn Error>> {
-        // Load and configure vertex shader
-        let vert = device
-            .create_shader()
-            .with_code(
-                ShaderFormat::SPIRV,
-                include_bytes!(concat!(env!("OUT_DIR"), "/imgui.vert.spv")),
-                ShaderStage::Vertex,
-            )
-            .with_uniform_buffers(1)
-            .with_entrypoint(c"main")
-            .build()?;
-
-        // Load and configure fragment shader
-        let frag = device
-            .create_shader()
-            .with_code(
-                ShaderFormat::SPIRV,
-                include_bytes!(concat!(env!("OUT_DIR"), "/imgui.frag.spv")),
-                ShaderStage::Fragment,
-            )
-            .with_samplers(1)
-            .with_entrypoint(c"main")
-            .build()?;
-
         let format = device.get_swapchain_texture_format(window);
+        let config = RendererConfig::new(format).with_color_space(swapchain_color_space(format));
+        Self::with_config(device, &config, imgui_context)
+    }
 
-        // Build the graphics pipeline
-        let pipeline = device
-            .create_graphics_pipeline()
-            .with_vertex_shader(&vert)
-            .with_vertex_input_state(
-                VertexInputState::new()
-                    .with_vertex_buffer_descriptions(&[VertexBufferDescription::new()
-                        .with_slot(0)
-                        .with_pitch(size_of::<DrawVert>() as u32)
-                        .with_input_rate(VertexInputRate::Vertex)
-                        .with_instance_step_rate(0)])
-                    .with_vertex_attributes(&[
-                        // Position
-                        VertexAttribute::new()
-                            .with_format(VertexElementFormat::Float2)
-                            .with_location(0)
-                            .with_buffer_slot(0)
-                            .with_offset(offset_of!(DrawVert, pos) as u32),
-                        // UV
-                        VertexAttribute::new()
-                            .with_format(VertexElementFormat::Float2)
-                            .with_location(1)
-                            .with_buffer_slot(0)
-                            .with_offset(offset_of!(DrawVert, uv) as u32),
-                        // Color
-                        VertexAttribute::new()
-                            .with_format(VertexElementFormat::Ubyte4Norm)
-                            .with_location(2)
-                            .with_buffer_slot(0)
-                            .with_offset(offset_of!(DrawVert, col) as u32),
-                    ]),
-            )
-            .with_rasterizer_state(
-                RasterizerState::new()
-                    .with_fill_mode(FillMode::Fill)
-                    .with_front_face(FrontFace::Clockwise), // Disable culling for UI geometry
-            )
-            .with_fragment_shader(&frag)
-            .with_primitive_type(PrimitiveType::TriangleList)
-            .with_target_info(
-                GraphicsPipelineTargetInfo::new().with_color_target_descriptions(&[ColorTargetDescription::new()
-                    .with_format(format)
-                    .with_blend_state(
-                        ColorTargetBlendState::new()
-                            .with_color_blend_op(BlendOp::Add)
-                            .with_src_color_blendfactor(BlendFactor::SrcAlpha)
-                            .with_dst_color_blendfactor(BlendFactor::OneMinusSrcAlpha)
-                            .with_alpha_blend_op(BlendOp::Add)
-                            .with_src_alpha_blendfactor(BlendFactor::One)
-                            .with_dst_alpha_blendfactor(BlendFactor::OneMinusSrcAlpha)
-                            .with_enable_blend(true),
-                    )]),
-            )
-            .build()?;
+    /// Creates a new ImGui SDL3 renderer with explicit settings, e.g. to render into
+    /// offscreen targets instead of the window swapchain.
+    pub fn with_config(
+        device: &Device,
+        config: &RendererConfig,
+        imgui_context: &mut imgui::Context,
+    ) -> Result<Self, Box<dyn Error>> {
+        // Draw commands are issued with a vertex offset
+        imgui_context
+            .io_mut()
+            .backend_flags
+            .insert(BackendFlags::RENDERER_HAS_VTX_OFFSET);
+
+        let (vert, frags) = create_shaders(device)?;
 
         // Upload the ImGui font texture to the GPU
-        let font_texture = create_imgui_font_texture(device, imgui_context)?;
-        // Create a texture sampler and bind font texture
-        let sampler = device.create_sampler(
-            SamplerCreateInfo::new()
-                .with_min_filter(Filter::Linear)
-                .with_mag_filter(Filter::Linear)
-                .with_mipmap_mode(SamplerMipmapMode::Linear)
-                .with_address_mode_u(SamplerAddressMode::ClampToEdge)
-                .with_address_mode_v(SamplerAddressMode::ClampToEdge)
-                .with_address_mode_w(SamplerAddressMode::ClampToEdge),
-        )?;
+        let font_texture = create_font_texture(device, imgui_context, config.filter, config.address_mode)?;
 
-        Ok(Self {
-            pipeline,
-            textures: vec![(font_texture, sampler)],
-        })
+        let mut renderer = Self {
+            pipelines: Vec::new(),
+            vertex_shader: vert,
+            fragment_shaders: frags,
+            target_format: config.target_format,
+            sample_count: config.sample_count,
+            textures: vec![Some(font_texture)],
+            frame_textures: Vec::new(),
+            alpha_mode: AlphaMode::Straight,
+            color_space: config.color_space,
+            layer: None,
+            sdr_white_level: DEFAULT_SDR_WHITE_LEVEL,
+            filter: config.filter,
+            address_mode: config.address_mode,
+        };
+        renderer.create_pipelines(device)?;
+
+        Ok(renderer)
+    }
+
+    /// Uploads the font atlas again, e.g. after fonts were added to the context.
+    pub fn reload_font_texture(
+        &mut self,
+        device: &Device,
+        imgui_context: &mut imgui::Context,
+    ) -> Result<(), Box<dyn Error>> {
+        self.textures[0] = Some(create_font_texture(
+            device,
+            imgui_context,
+            self.filter,
+            self.address_mode,
+        )?);
+        Ok(())
+    }
+
+    /// Recreates the shaders, pipelines and font atlas on a new device, after the
+    /// previous one was lost.
+    ///
+    /// Textures registered by the application belonged to the lost device: they are
+    /// released, and their ids may be handed out again to new textures.
+    pub fn recreate_device_objects(
+        &mut self,
+        device: &Device,
+        imgui_context: &mut imgui::Context,
+    ) -> Result<(), Box<dyn Error>> {
+        let (vert, frags) = create_shaders(device)?;
+        self.vertex_shader = vert;
+        self.fragment_shaders = frags;
+        self.create_pipelines(device)?;
+
+        self.frame_textures.clear();
+        self.textures.clear();
+        self.textures.push(Some(create_font_texture(
+            device,
+            imgui_context,
+            self.filter,
+            self.address_mode,
+        )?));
+
+        Ok(())
+    }
+
+    /// Returns the texture format the pipeline renders to.
+    pub fn target_format(&self) -> TextureFormat {
+        self.target_format
+    }
+
+    /// Returns the samples per pixel of the render targets.
+    pub fn sample_count(&self) -> SampleCount {
+        self.sample_count
+    }
+
+    /// Rebuilds the graphics pipeline for a new render target format.
+    ///
+    /// This must be called when the swapchain composition changes, since the swapchain
+    /// texture format changes with it, along with `set_color_space`.
+    pub fn set_target_format(&mut self, device: &Device, format: TextureFormat) -> Result<(), Box<dyn Error>> {
+        if format == self.target_format {
+            return Ok(());
+        }
+
+        let previous = std::mem::replace(&mut self.target_format, format);
+        self.create_pipelines(device)
+            .inspect_err(|_| self.target_format = previous)
+    }
+
+    /// Returns the alpha mode of the colors written to the render target.
+    pub fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    /// Switches between straight and premultiplied alpha output.
+    ///
+    /// Premultiplied output is what you want when rendering the UI into an offscreen
+    /// texture that is later composited over the scene with `One`/`OneMinusSrcAlpha`.
+    pub fn set_alpha_mode(&mut self, device: &Device, alpha_mode: AlphaMode) -> Result<(), Box<dyn Error>> {
+        if alpha_mode == self.alpha_mode {
+            return Ok(());
+        }
+
+        let previous = std::mem::replace(&mut self.alpha_mode, alpha_mode);
+        self.create_pipelines(device)
+            .inspect_err(|_| self.alpha_mode = previous)
+    }
+
+    /// Returns the color space the renderer encodes ImGui colors for.
+    pub fn color_space(&self) -> SwapchainComposition {
+        self.color_space
+    }
+
+    /// Changes the color space ImGui colors are encoded for, e.g. after the swapchain
+    /// composition changed.
+    ///
+    /// On `Hdr10St2084` targets, the UI is blended in an intermediate linear layer which is
+    /// then PQ-encoded onto the target: translucent pixels blend with the existing content
+    /// of the target in PQ space.
+    pub fn set_color_space(
+        &mut self,
+        device: &Device,
+        color_space: SwapchainComposition,
+    ) -> Result<(), Box<dyn Error>> {
+        if color_space == self.color_space {
+            return Ok(());
+        }
+
+        let previous = std::mem::replace(&mut self.color_space, color_space);
+        self.create_pipelines(device)
+            .inspect_err(|_| self.color_space = previous)
+    }
+
+    /// Returns the brightness of the UI on HDR targets, in nits.
+    pub fn sdr_white_level(&self) -> f32 {
+        self.sdr_white_level
+    }
+
+    /// Sets the brightness of the UI on HDR targets, in nits.
+    ///
+    /// This has no effect on SDR targets.
+    pub fn set_sdr_white_level(&mut self, nits: f32) {
+        self.sdr_white_level = nits.max(0.0);
     }
 
     pub fn push_texture(&mut self, texture: Texture<'static>, sampler: Sampler) -> TextureId {
-        let id = TextureId::from(self.textures.len());
-        self.textures.push((texture, sampler));
+        self.push_texture_with_options(texture, sampler, TextureOptions::default())
+    }
+
+    /// Registers a texture for the current frame, with options describing its content.
+    pub fn push_texture_with_options(
+        &mut self,
+        texture: Texture<'static>,
+        sampler: Sampler,
+        options: TextureOptions,
+    ) -> TextureId {
+        let id = self.register_texture_with_options(texture, sampler, options);
+        self.frame_textures.push(id);
         id
     }
 
+    /// Returns the sampler used for the font atlas (linear filtering, clamped to edge
+    /// unless configured otherwise), a sensible default for UI images.
+    pub fn default_sampler(&self) -> Sampler {
+        self.textures[0].as_ref().unwrap().sampler.clone()
+    }
+
+    /// Registers a texture until it is unregistered, so that its `TextureId` stays
+    /// valid across frames.
+    pub fn register_texture(&mut self, texture: Texture<'static>, sampler: Sampler) -> TextureId {
+        self.register_texture_with_options(texture, sampler, TextureOptions::default())
+    }
+
+    /// Registers a texture across frames, with options describing its content.
+    pub fn register_texture_with_options(
+        &mut self,
+        texture: Texture<'static>,
+        sampler: Sampler,
+        options: TextureOptions,
+    ) -> TextureId {
+        let texture = Some(RegisteredTexture {
+            texture,
+            sampler,
+            options,
+            format: options.format,
+            staging: None,
+        });
+
+        // Reuse the first free slot, the font atlas slot is never freed
+        match self.textures.iter().position(Option::is_none) {
+            Some(index) => {
+                self.textures[index] = texture;
+                TextureId::from(index)
+            }
+            None => {
+                self.textures.push(texture);
+                TextureId::from(self.textures.len() - 1)
+            }
+        }
+    }
+
+    /// Releases a registered texture, returning it along with its sampler.
+    ///
+    /// The font atlas cannot be unregistered.
+    pub fn unregister_texture(&mut self, id: TextureId) -> Option<(Texture<'static>, Sampler)> {
+        if id.id() == 0 {
+            return None;
+        }
+
+        // The slot may be reused before the end of the frame, which must not release it
+        self.frame_textures.retain(|&frame_id| frame_id != id);
+        self.textures
+            .get_mut(id.id())
+            .and_then(Option::take)
+            .map(|registered| (registered.texture, registered.sampler))
+    }
+
+    /// Swaps the texture behind a registered `TextureId`, returning the previous one.
+    ///
+    /// Draws already recorded with this id keep working: the previous texture stays
+    /// alive until the GPU is done with it.
+    pub fn replace_texture(
+        &mut self,
+        id: TextureId,
+        texture: Texture<'static>,
+        sampler: Sampler,
+        options: TextureOptions,
+    ) -> Option<(Texture<'static>, Sampler)> {
+        if id.id() == 0 {
+            return None;
+        }
+
+        let registered = self.textures.get_mut(id.id()).and_then(Option::as_mut)?;
+        let previous = std::mem::replace(
+            registered,
+            RegisteredTexture {
+                texture,
+                sampler,
+                options,
+                format: options.format,
+                staging: None,
+            },
+        );
+        Some((previous.texture, previous.sampler))
+    }
+
+    /// Replaces the options of a registered texture.
+    pub fn set_texture_options(&mut self, id: TextureId, options: TextureOptions) {
+        if let Some(Some(registered)) = self.textures.get_mut(id.id()) {
+            registered.options = options;
+        }
+    }
+
+    /// Uploads pixels to a region of a registered texture, keeping its `TextureId`.
+    ///
+    /// The data holds tightly packed rows in the format the texture was registered with
+    /// (see `TextureOptions::format`). Regions of block-compressed textures must be aligned
+    /// on blocks, except where they reach the texture edges.
+    ///
+    /// This is meant to be called every frame for video playback or live previews: the
+    /// upload buffer is kept with the texture and mapped with cycling enabled, so SDL
+    /// hands out a fresh buffer while the previous upload is still in flight instead
+    /// of stalling or overwriting it.
+    pub fn update_texture(
+        &mut self,
+        device: &Device,
+        id: TextureId,
+        region: Rect,
+        data: &[u8],
+    ) -> Result<(), Box<dyn Error>> {
+        let registered = self
+            .textures
+            .get_mut(id.id())
+            .and_then(Option::as_mut)
+            .ok_or("unknown texture id")?;
+
+        let (x, y) = (region.x().max(0) as u32, region.y().max(0) as u32);
+        if region.x() < 0
+            || region.y() < 0
+            || x + region.width() > registered.texture.width()
+            || y + region.height() > registered.texture.height()
+        {
+            return Err("region is out of the texture bounds".into());
+        }
+
+        let format = registered.format;
+        let block = format.block_dimension();
+        let aligned = |start: u32, size: u32, texture_size: u32| {
+            start.is_multiple_of(block) && (size.is_multiple_of(block) || start + size == texture_size)
+        };
+        if !aligned(x, region.width(), registered.texture.width())
+            || !aligned(y, region.height(), registered.texture.height())
+        {
+            return Err(format!("region is not aligned on the {block}x{block} blocks of {format:?}").into());
+        }
+
+        let size_bytes = format.row_pitch(region.width()) * format.row_count(region.height());
+        if data.len() != size_bytes as usize {
+            return Err(format!("expected {size_bytes} bytes of {format:?} data, got {}", data.len()).into());
+        }
+
+        // Grow the upload buffer when a bigger region comes in
+        if registered
+            .staging
+            .as_ref()
+            .is_none_or(|staging| staging.len() < size_bytes)
+        {
+            registered.staging = Some(
+                device
+                    .create_transfer_buffer()
+                    .with_size(size_bytes)
+                    .with_usage(TransferBufferUsage::UPLOAD)
+                    .build()?,
+            );
+        }
+        let staging = registered.staging.as_ref().unwrap();
+
+        let mut map = staging.map::<u8>(device, true);
+        map.mem_mut()[..data.len()].copy_from_slice(data);
+        map.unmap();
+
+        let copy_commands = device.acquire_command_buffer()?;
+        let copy_pass = device.begin_copy_pass(&copy_commands)?;
+
+        copy_pass.upload_to_gpu_texture(
+            TextureTransferInfo::new()
+                .with_transfer_buffer(staging)
+                .with_offset(0)
+                .with_pixels_per_row(region.width().next_multiple_of(block))
+                .with_rows_per_layer(region.height().next_multiple_of(block)),
+            TextureRegion::new()
+                .with_texture(&registered.texture)
+                .with_layer(0)
+                .with_x(x)
+                .with_y(y)
+                .with_width(region.width())
+                .with_height(region.height())
+                .with_depth(1),
+            false,
+        );
+
+        device.end_copy_pass(copy_pass);
+        copy_commands.submit()?;
+
+        Ok(())
+    }
+
     /// Renders the current ImGui draw data into the window.
     ///
     /// This function:
@@ -146,22 +805,48 @@ impl Renderer {
         color_targets: &[ColorTargetInfo],
         imgui_context: &mut imgui::Context,
     ) -> Result<(), Box<dyn Error>> {
-        let io = imgui_context.io();
-        let [width, height] = io.display_size;
-        let [scale_w, scale_h] = io.display_framebuffer_scale;
+        let draw_data = imgui_context.render();
+        self.render_draw_data(device, command_buffer, color_targets, draw_data)
+    }
+
+    /// Renders draw data already finalized with `imgui::Context::render`.
+    pub fn render_draw_data(
+        &mut self,
+        device: &Device,
+        command_buffer: &mut CommandBuffer,
+        color_targets: &[ColorTargetInfo],
+        draw_data: &DrawData,
+    ) -> Result<(), Box<dyn Error>> {
+        let [width, height] = draw_data.display_size;
+        let [scale_w, scale_h] = draw_data.framebuffer_scale;
 
         let fb_width = width * scale_w;
         let fb_height = height * scale_h;
-
-        let draw_data = imgui_context.render();
+        let coordinates = Coordinates::from_draw_data(draw_data);
 
         // Skip rendering if there's nothing to draw
         if width == 0.0 || height == 0.0 || draw_data.total_vtx_count == 0 || draw_data.total_idx_count == 0 {
+            self.release_frame_textures();
             return Ok(());
         }
 
-        let render_pass = device.begin_render_pass(command_buffer, color_targets, None)?;
-        render_pass.bind_graphics_pipeline(&self.pipeline);
+        // On HDR10 targets, blend the UI in the linear layer and encode it afterwards
+        let layer_targets;
+        let draw_targets = match &mut self.layer {
+            Some(layer) => {
+                let texture = layer.texture(device, fb_width.ceil() as u32, fb_height.ceil() as u32)?;
+                layer_targets = [ColorTargetInfo::default()
+                    .with_texture(texture)
+                    .with_load_op(LoadOp::CLEAR)
+                    .with_store_op(StoreOp::STORE)];
+                &layer_targets[..]
+            }
+            None => color_targets,
+        };
+
+        let render_pass = device.begin_render_pass(command_buffer, draw_targets, None)?;
+        let mut variant = ShaderVariant::Default;
+        self.pipelines[variant as usize].bind(&render_pass);
 
         // Flatten all draw data into a single vertex/index buffer
         let mut vtx_data = Vec::with_capacity(draw_data.total_vtx_count as usize);
@@ -214,15 +899,27 @@ impl Renderer {
         // Set viewport and projection matrix
         device.set_viewport(&render_pass, Viewport::new(0.0, 0.0, fb_width, fb_height, 0.0, 1.0));
 
-        // Push orthographic projection matrix
+        // Push orthographic projection matrix, mapping the display rect to the target
+        let [left, top] = draw_data.display_pos;
         let matrix = [
             [2.0 / width, 0.0, 0.0, 0.0],
             [0.0, 2.0 / -height, 0.0, 0.0],
             [0.0, 0.0, -1.0, 0.0],
-            [-1.0, 1.0, 0.0, 1.0],
+            [-1.0 - 2.0 * left / width, 1.0 + 2.0 * top / height, 0.0, 1.0],
         ];
         command_buffer.push_vertex_uniform_data(0, &matrix);
 
+        let (color_space, alpha_mode) = match self.layer {
+            Some(_) => (COLOR_SPACE_HDR10_LAYER, AlphaMode::Premultiplied),
+            None => (self.color_space as u32, self.alpha_mode),
+        };
+        let mut output = OutputUniforms {
+            color_space,
+            sdr_white_level: self.sdr_white_level,
+            flags: 0,
+            _padding: 0,
+        };
+
         // Render each draw command
         let mut voffset = 0;
         let mut ioffset = 0;
@@ -234,27 +931,44 @@ impl Renderer {
                         count,
                         cmd_params:
                             DrawCmdParams {
-                                clip_rect: [x, y, w, h],
+                                clip_rect,
                                 idx_offset,
                                 vtx_offset,
                                 texture_id,
                             },
                     } => {
                         // Calculate scissor rectangle
-                        let scissor_x = (x * scale_w) as i32;
-                        let scissor_y = (y * scale_h) as i32;
-                        let scissor_w = ((w - x) * scale_w).max(0.0) as u32;
-                        let scissor_h = ((h - y) * scale_h).max(0.0) as u32;
+                        let [min_x, min_y, max_x, max_y] = coordinates.clip_rect_to_pixels(clip_rect);
 
                         // Skip if scissor is invalid
-                        if scissor_w > 0 && scissor_h > 0 {
-                            render_pass.set_scissor(Rect::new(scissor_x, scissor_y, scissor_w, scissor_h));
+                        if max_x > min_x && max_y > min_y {
+                            let (width, height) = ((max_x - min_x) as u32, (max_y - min_y) as u32);
+                            render_pass.set_scissor(Rect::new(min_x, min_y, width, height));
                         } else {
                             continue;
                         }
 
-                        let (t, s) = &self.textures[texture_id.id()];
-                        let binding = TextureSamplerBinding::new().with_texture(t).with_sampler(s);
+                        // Skip draws referencing textures that were unregistered
+                        let Some(Some(texture)) = self.textures.get(texture_id.id()) else {
+                            continue;
+                        };
+
+                        output.flags = output_flags(texture_id, &texture.options, alpha_mode);
+                        command_buffer.push_fragment_uniform_data(0, &output);
+
+                        // Switch to the shader variant able to apply the texture view
+                        let texture_variant = texture.options.view.variant();
+                        if texture_variant != variant {
+                            variant = texture_variant;
+                            self.pipelines[variant as usize].bind(&render_pass);
+                        }
+                        if variant != ShaderVariant::Default {
+                            command_buffer.push_fragment_uniform_data(1, &ViewUniforms::from(&texture.options.view));
+                        }
+
+                        let binding = TextureSamplerBinding::new()
+                            .with_texture(&texture.texture)
+                            .with_sampler(&texture.sampler);
                         render_pass.bind_fragment_samplers(0, &[binding]);
 
                         // Draw the elements
@@ -281,11 +995,409 @@ impl Renderer {
 
         device.end_render_pass(render_pass);
 
-        // Clear all textures except the font, which is always 0.
-        self.textures.drain(1..);
+        if let Some(layer) = &self.layer {
+            layer.composite(device, command_buffer, color_targets, fb_width, fb_height)?;
+        }
+
+        self.release_frame_textures();
+
+        Ok(())
+    }
+}
+
+impl RendererBackend for Renderer {
+    type Device = Device;
+    type Texture = (Texture<'static>, Sampler);
+    type Target<'a> = GpuTarget<'a>;
+
+    fn init(&mut self, device: &Device, imgui_context: &mut imgui::Context) -> Result<(), Box<dyn Error>> {
+        imgui_context
+            .io_mut()
+            .backend_flags
+            .insert(BackendFlags::RENDERER_HAS_VTX_OFFSET);
+        self.reload_font_texture(device, imgui_context)
+    }
+
+    fn register_texture(&mut self, (texture, sampler): Self::Texture) -> TextureId {
+        Renderer::register_texture(self, texture, sampler)
+    }
+
+    fn push_texture(&mut self, (texture, sampler): Self::Texture) -> TextureId {
+        Renderer::push_texture(self, texture, sampler)
+    }
+
+    fn unregister_texture(&mut self, id: TextureId) -> Option<Self::Texture> {
+        Renderer::unregister_texture(self, id)
+    }
+
+    fn render_draw_data(&mut self, target: GpuTarget<'_>, draw_data: &DrawData) -> Result<(), Box<dyn Error>> {
+        Renderer::render_draw_data(
+            self,
+            target.device,
+            target.command_buffer,
+            target.color_targets,
+            draw_data,
+        )
+    }
+
+    fn recreate_device_objects(
+        &mut self,
+        device: &Device,
+        imgui_context: &mut imgui::Context,
+    ) -> Result<(), Box<dyn Error>> {
+        Renderer::recreate_device_objects(self, device, imgui_context)
+    }
+}
+
+impl Renderer {
+    /// Builds the pipelines for the current target format, alpha mode and color space,
+    /// the previous ones are kept on failure.
+    fn create_pipelines(&mut self, device: &Device) -> Result<(), Box<dyn Error>> {
+        let supported = self.sample_count == SampleCount::NoMultiSampling
+            || unsafe {
+                SDL_GPUTextureSupportsSampleCount(
+                    device.raw(),
+                    SDL_GPUTextureFormat(self.target_format as i32),
+                    SDL_GPUSampleCount(self.sample_count as i32),
+                )
+            };
+        if !supported {
+            return Err(format!(
+                "{:?} targets do not support {:?}",
+                self.target_format, self.sample_count
+            )
+            .into());
+        }
+
+        if self.color_space == SwapchainComposition::Hdr10St2084 {
+            // The layer always holds premultiplied colors, the composite pass writes the alpha mode.
+            // It is single-sampled, only the composite pass draws to the targets.
+            let pipelines = create_pipelines(
+                device,
+                &self.vertex_shader,
+                &self.fragment_shaders,
+                LAYER_FORMAT,
+                SampleCount::NoMultiSampling,
+                AlphaMode::Premultiplied,
+            )?;
+            self.layer = Some(LinearLayer::new(device, self.target_format, self.sample_count)?);
+            self.pipelines = pipelines;
+        } else {
+            self.pipelines = create_pipelines(
+                device,
+                &self.vertex_shader,
+                &self.fragment_shaders,
+                self.target_format,
+                self.sample_count,
+                self.alpha_mode,
+            )?;
+            self.layer = None;
+        }
 
         Ok(())
     }
+
+    /// Releases the textures pushed for the current frame only.
+    fn release_frame_textures(&mut self) {
+        for id in self.frame_textures.drain(..) {
+            self.textures[id.id()] = None;
+        }
+    }
+}
+
+/// Loads the vertex shader and the fragment shader variants, in `ShaderVariant` order.
+fn create_shaders(device: &Device) -> Result<(Shader, Vec<Shader>), sdl3::Error> {
+    let vert = device
+        .create_shader()
+        .with_code(
+            ShaderFormat::SPIRV,
+            include_bytes!(concat!(env!("OUT_DIR"), "/imgui.vert.spv")),
+            ShaderStage::Vertex,
+        )
+        .with_uniform_buffers(1)
+        .with_entrypoint(c"main")
+        .build()?;
+
+    let frags = vec![
+        create_fragment_shader(device, include_bytes!(concat!(env!("OUT_DIR"), "/imgui.frag.spv")), 1)?,
+        create_fragment_shader(
+            device,
+            include_bytes!(concat!(env!("OUT_DIR"), "/imgui_view.frag.spv")),
+            2,
+        )?,
+        create_fragment_shader(
+            device,
+            include_bytes!(concat!(env!("OUT_DIR"), "/imgui_view_array.frag.spv")),
+            2,
+        )?,
+    ];
+
+    Ok((vert, frags))
+}
+
+/// Loads a fragment shader variant from its SPIR-V code.
+fn create_fragment_shader(device: &Device, code: &[u8], uniform_buffers: u32) -> Result<Shader, sdl3::Error> {
+    device
+        .create_shader()
+        .with_code(ShaderFormat::SPIRV, code, ShaderStage::Fragment)
+        .with_samplers(1)
+        .with_uniform_buffers(uniform_buffers)
+        .with_entrypoint(c"main")
+        .build()
+}
+
+/// Builds one graphics pipeline per fragment shader variant.
+fn create_pipelines(
+    device: &Device,
+    vert: &Shader,
+    frags: &[Shader],
+    format: TextureFormat,
+    sample_count: SampleCount,
+    alpha_mode: AlphaMode,
+) -> Result<Vec<Pipeline>, sdl3::Error> {
+    frags
+        .iter()
+        .map(|frag| create_pipeline(device, vert, frag, format, sample_count, alpha_mode))
+        .collect()
+}
+
+/// Builds the graphics pipeline used to draw ImGui geometry into targets of the given format.
+fn create_pipeline(
+    device: &Device,
+    vert: &Shader,
+    frag: &Shader,
+    format: TextureFormat,
+    sample_count: SampleCount,
+    alpha_mode: AlphaMode,
+) -> Result<Pipeline, sdl3::Error> {
+    // Premultiplied colors already carry their coverage
+    let src_color_blendfactor = match alpha_mode {
+        AlphaMode::Straight => SDL_GPU_BLENDFACTOR_SRC_ALPHA,
+        AlphaMode::Premultiplied => SDL_GPU_BLENDFACTOR_ONE,
+    };
+
+    let vertex_buffer_descriptions = [SDL_GPUVertexBufferDescription {
+        slot: 0,
+        pitch: size_of::<DrawVert>() as u32,
+        input_rate: SDL_GPU_VERTEXINPUTRATE_VERTEX,
+        instance_step_rate: 0,
+    }];
+    let vertex_attributes = [
+        // Position
+        SDL_GPUVertexAttribute {
+            location: 0,
+            buffer_slot: 0,
+            format: SDL_GPU_VERTEXELEMENTFORMAT_FLOAT2,
+            offset: offset_of!(DrawVert, pos) as u32,
+        },
+        // UV
+        SDL_GPUVertexAttribute {
+            location: 1,
+            buffer_slot: 0,
+            format: SDL_GPU_VERTEXELEMENTFORMAT_FLOAT2,
+            offset: offset_of!(DrawVert, uv) as u32,
+        },
+        // Color
+        SDL_GPUVertexAttribute {
+            location: 2,
+            buffer_slot: 0,
+            format: SDL_GPU_VERTEXELEMENTFORMAT_UBYTE4_NORM,
+            offset: offset_of!(DrawVert, col) as u32,
+        },
+    ];
+    let vertex_input_state = SDL_GPUVertexInputState {
+        vertex_buffer_descriptions: vertex_buffer_descriptions.as_ptr(),
+        num_vertex_buffers: vertex_buffer_descriptions.len() as u32,
+        vertex_attributes: vertex_attributes.as_ptr(),
+        num_vertex_attributes: vertex_attributes.len() as u32,
+    };
+
+    Pipeline::new(
+        device,
+        [vert, frag],
+        vertex_input_state,
+        format,
+        sample_count,
+        src_color_blendfactor,
+    )
+}
+
+/// Builds the pipeline compositing the HDR10 linear layer onto targets of the given format.
+///
+/// The composite shader writes premultiplied colors, which blends the same as straight
+/// colors with `SrcAlpha` in both alpha modes.
+fn create_composite_pipeline(
+    device: &Device,
+    format: TextureFormat,
+    sample_count: SampleCount,
+) -> Result<Pipeline, sdl3::Error> {
+    let vert = device
+        .create_shader()
+        .with_code(
+            ShaderFormat::SPIRV,
+            include_bytes!(concat!(env!("OUT_DIR"), "/composite.vert.spv")),
+            ShaderStage::Vertex,
+        )
+        .with_entrypoint(c"main")
+        .build()?;
+    let frag = create_fragment_shader(
+        device,
+        include_bytes!(concat!(env!("OUT_DIR"), "/composite.frag.spv")),
+        0,
+    )?;
+
+    // The full screen triangle comes from the vertex index
+    Pipeline::new(
+        device,
+        [&vert, &frag],
+        SDL_GPUVertexInputState::default(),
+        format,
+        sample_count,
+        SDL_GPU_BLENDFACTOR_ONE,
+    )
+}
+
+/// A graphics pipeline created with the raw SDL API, the sdl3 pipeline builder does not
+/// expose the multisample state.
+struct Pipeline {
+    raw: NonNull<SDL_GPUGraphicsPipeline>,
+    /// Device the pipeline is released from.
+    device: Device,
+}
+
+impl Pipeline {
+    /// Creates a pipeline drawing triangles blended over a single color target.
+    fn new(
+        device: &Device,
+        [vert, frag]: [&Shader; 2],
+        vertex_input_state: SDL_GPUVertexInputState,
+        format: TextureFormat,
+        sample_count: SampleCount,
+        src_color_blendfactor: SDL_GPUBlendFactor,
+    ) -> Result<Self, sdl3::Error> {
+        let color_target_descriptions = [SDL_GPUColorTargetDescription {
+            format: SDL_GPUTextureFormat(format as i32),
+            blend_state: SDL_GPUColorTargetBlendState {
+                color_blend_op: SDL_GPU_BLENDOP_ADD,
+                src_color_blendfactor,
+                dst_color_blendfactor: SDL_GPU_BLENDFACTOR_ONE_MINUS_SRC_ALPHA,
+                alpha_blend_op: SDL_GPU_BLENDOP_ADD,
+                src_alpha_blendfactor: SDL_GPU_BLENDFACTOR_ONE,
+                dst_alpha_blendfactor: SDL_GPU_BLENDFACTOR_ONE_MINUS_SRC_ALPHA,
+                enable_blend: true,
+                ..Default::default()
+            },
+        }];
+
+        let create_info = SDL_GPUGraphicsPipelineCreateInfo {
+            vertex_shader: vert.raw(),
+            fragment_shader: frag.raw(),
+            vertex_input_state,
+            primitive_type: SDL_GPU_PRIMITIVETYPE_TRIANGLELIST,
+            // Disable culling for UI geometry
+            rasterizer_state: SDL_GPURasterizerState {
+                fill_mode: SDL_GPU_FILLMODE_FILL,
+                cull_mode: SDL_GPU_CULLMODE_NONE,
+                front_face: SDL_GPU_FRONTFACE_CLOCKWISE,
+                ..Default::default()
+            },
+            multisample_state: SDL_GPUMultisampleState {
+                sample_count: SDL_GPUSampleCount(sample_count as i32),
+                ..Default::default()
+            },
+            target_info: SDL_GPUGraphicsPipelineTargetInfo {
+                color_target_descriptions: color_target_descriptions.as_ptr(),
+                num_color_targets: color_target_descriptions.len() as u32,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let raw = unsafe { SDL_CreateGPUGraphicsPipeline(device.raw(), &create_info) };
+        let raw = NonNull::new(raw).ok_or_else(sdl3::get_error)?;
+        Ok(Self {
+            raw,
+            device: device.clone(),
+        })
+    }
+
+    fn bind(&self, render_pass: &RenderPass) {
+        unsafe { SDL_BindGPUGraphicsPipeline(render_pass.raw(), self.raw.as_ptr()) };
+    }
+}
+
+impl Drop for Pipeline {
+    fn drop(&mut self) {
+        unsafe { SDL_ReleaseGPUGraphicsPipeline(self.device.raw(), self.raw.as_ptr()) };
+    }
+}
+
+/// Computes the fragment shader flags for a draw command.
+fn output_flags(texture_id: TextureId, options: &TextureOptions, alpha_mode: AlphaMode) -> u32 {
+    let mut flags = 0;
+
+    // Only the font atlas holds anti-aliased coverage in its alpha channel
+    if texture_id.id() == 0 {
+        flags |= OUTPUT_FLAG_LINEARIZE_ALPHA;
+    }
+    if options.alpha_mode == AlphaMode::Premultiplied {
+        flags |= OUTPUT_FLAG_PREMULTIPLIED_TEXTURE;
+    }
+    if alpha_mode == AlphaMode::Premultiplied {
+        flags |= OUTPUT_FLAG_PREMULTIPLIED_OUTPUT;
+    }
+    // Tone mapping already brings float values back to sRGB
+    if options.encoding == ColorEncoding::Linear && options.view.tone_mapping == ToneMapping::None {
+        flags |= OUTPUT_FLAG_LINEAR_TEXTURE;
+    }
+
+    flags
+}
+
+/// Deduces the composition of a window swapchain from its texture format.
+///
+/// sRGB formats are written through hardware encoding and need linear colors, while
+/// 16-bit float and 10-bit formats are the ones SDL picks for HDR swapchains. This only
+/// holds for swapchains: offscreen targets state their color space in `RendererConfig`.
+pub(crate) fn swapchain_color_space(format: TextureFormat) -> SwapchainComposition {
+    match format {
+        TextureFormat::B8g8r8a8UnormSrgb | TextureFormat::R8g8b8a8UnormSrgb => SwapchainComposition::SdrLinear,
+        TextureFormat::R16g16b16a16Float => SwapchainComposition::HdrExtendedLinear,
+        TextureFormat::R10g10b10a2Unorm => SwapchainComposition::Hdr10St2084,
+        _ => SwapchainComposition::Sdr,
+    }
+}
+
+/// Uploads the ImGui font atlas and creates the default sampler it is drawn with.
+fn create_font_texture(
+    device: &Device,
+    imgui_context: &mut imgui::Context,
+    filter: Filter,
+    address_mode: SamplerAddressMode,
+) -> Result<RegisteredTexture, Box<dyn Error>> {
+    let texture = create_imgui_font_texture(device, imgui_context)?;
+
+    let mipmap_mode = match filter {
+        Filter::Nearest => SamplerMipmapMode::Nearest,
+        Filter::Linear => SamplerMipmapMode::Linear,
+    };
+    let sampler = device.create_sampler(
+        SamplerCreateInfo::new()
+            .with_min_filter(filter)
+            .with_mag_filter(filter)
+            .with_mipmap_mode(mipmap_mode)
+            .with_address_mode_u(address_mode)
+            .with_address_mode_v(address_mode)
+            .with_address_mode_w(address_mode),
+    )?;
+
+    Ok(RegisteredTexture {
+        texture,
+        sampler,
+        options: TextureOptions::default(),
+        format: PixelFormat::Rgba8,
+        staging: None,
+    })
 }
 
 /// Uploads the ImGui font atlas to the GPU and returns the resulting texture.
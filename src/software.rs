@@ -0,0 +1,584 @@
+use std::error::Error;
+
+use imgui::{BackendFlags, DrawCmdParams, DrawData, DrawVert, TextureId, internal::RawWrapper};
+use sdl3::gpu::Filter;
+
+use crate::{backend::RendererBackend, coordinates::Coordinates};
+
+/// An RGBA8 image, as rendered by `SoftwareRenderer` or drawn by it as a texture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    /// Tightly packed rows of RGBA pixels.
+    data: Vec<u8>,
+}
+
+impl Image {
+    /// Creates a transparent black image.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    /// Wraps tightly packed RGBA pixels.
+    pub fn from_rgba(width: u32, height: u32, data: Vec<u8>) -> Result<Self, Box<dyn Error>> {
+        if data.len() != width as usize * height as usize * 4 {
+            return Err(format!("expected {width}x{height} RGBA pixels, got {} bytes", data.len()).into());
+        }
+
+        Ok(Self { width, height, data })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the pixels, in rows of RGBA values.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Returns the RGBA value of a pixel, panics if it is out of bounds.
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        assert!(x < self.width && y < self.height, "pixel ({x}, {y}) out of bounds");
+        let offset = (y as usize * self.width as usize + x as usize) * 4;
+        self.data[offset..offset + 4].try_into().unwrap()
+    }
+
+    /// Sets every pixel to `color`, e.g. to clear the image before rendering.
+    pub fn fill(&mut self, color: [u8; 4]) {
+        for pixel in self.data.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&color);
+        }
+    }
+}
+
+/// Renderer backend for imgui rasterizing on the CPU into an `Image`.
+///
+/// It needs neither a GPU nor a window, which makes it suitable for deterministic UI
+/// tests: the output only depends on the draw data. It reproduces the SDR path of
+/// `renderer::Renderer` with straight alpha, that is vertex colors multiplied by the
+/// sampled texture, scissor rects and `SrcAlpha, OneMinusSrcAlpha` blending.
+///
+/// Pixels are sampled at their center and covered following the top-left rule, like GPUs
+/// do. Results are close to a GPU render, but not bit-exact.
+pub struct SoftwareRenderer {
+    /// Registered textures indexed by `TextureId`, the font atlas is always 0.
+    textures: Vec<Option<Image>>,
+    /// Textures pushed for the current frame only, released after rendering.
+    frame_textures: Vec<TextureId>,
+    /// Filter textures are sampled with.
+    filter: Filter,
+}
+
+impl SoftwareRenderer {
+    /// Creates a new ImGui software renderer and copies the ImGui font atlas.
+    ///
+    /// Textures are sampled with a linear filter, like the default sampler of `renderer::Renderer`.
+    pub fn new(imgui_context: &mut imgui::Context) -> Self {
+        // Draw commands are issued with a vertex offset
+        imgui_context
+            .io_mut()
+            .backend_flags
+            .insert(BackendFlags::RENDERER_HAS_VTX_OFFSET);
+
+        Self {
+            textures: vec![Some(create_font_texture(imgui_context))],
+            frame_textures: Vec::new(),
+            filter: Filter::Linear,
+        }
+    }
+
+    /// Sets the filter textures are sampled with.
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Copies the font atlas again, e.g. after fonts were added to the context.
+    pub fn reload_font_texture(&mut self, imgui_context: &mut imgui::Context) {
+        self.textures[0] = Some(create_font_texture(imgui_context));
+    }
+
+    /// Registers a texture for the current frame.
+    pub fn push_texture(&mut self, texture: Image) -> TextureId {
+        let id = self.register_texture(texture);
+        self.frame_textures.push(id);
+        id
+    }
+
+    /// Registers a texture until it is unregistered, so that its `TextureId` stays
+    /// valid across frames.
+    pub fn register_texture(&mut self, texture: Image) -> TextureId {
+        let texture = Some(texture);
+
+        // Reuse the first free slot, the font atlas slot is never freed
+        match self.textures.iter().position(Option::is_none) {
+            Some(index) => {
+                self.textures[index] = texture;
+                TextureId::from(index)
+            }
+            None => {
+                self.textures.push(texture);
+                TextureId::from(self.textures.len() - 1)
+            }
+        }
+    }
+
+    /// Releases a registered texture, returning it.
+    ///
+    /// The font atlas cannot be unregistered.
+    pub fn unregister_texture(&mut self, id: TextureId) -> Option<Image> {
+        if id.id() == 0 {
+            return None;
+        }
+
+        // The slot may be reused before the end of the frame, which must not release it
+        self.frame_textures.retain(|&frame_id| frame_id != id);
+        self.textures.get_mut(id.id()).and_then(Option::take)
+    }
+
+    /// Returns a registered texture, e.g. to update its content.
+    pub fn texture_mut(&mut self, id: TextureId) -> Option<&mut Image> {
+        self.textures.get_mut(id.id()).and_then(Option::as_mut)
+    }
+
+    /// Renders the current ImGui draw data into the image.
+    ///
+    /// The image is not cleared, the UI is blended over its content.
+    pub fn render(&mut self, target: &mut Image, imgui_context: &mut imgui::Context) {
+        let draw_data = imgui_context.render();
+        self.render_draw_data(target, draw_data);
+    }
+
+    /// Renders draw data already finalized with `imgui::Context::render`.
+    ///
+    /// The image is expected to be `display_size * framebuffer_scale` pixels large,
+    /// anything outside of it is clipped.
+    pub fn render_draw_data(&mut self, target: &mut Image, draw_data: &DrawData) {
+        let [width, height] = draw_data.display_size;
+        let coordinates = Coordinates::from_draw_data(draw_data);
+
+        // Skip rendering if there's nothing to draw
+        if width == 0.0 || height == 0.0 || draw_data.total_vtx_count == 0 || draw_data.total_idx_count == 0 {
+            self.release_frame_textures();
+            return;
+        }
+
+        for draw_list in draw_data.draw_lists() {
+            let vertices = draw_list.vtx_buffer();
+            let indices = draw_list.idx_buffer();
+
+            for draw_cmd in draw_list.commands() {
+                match draw_cmd {
+                    imgui::DrawCmd::Elements {
+                        count,
+                        cmd_params:
+                            DrawCmdParams {
+                                clip_rect,
+                                idx_offset,
+                                vtx_offset,
+                                texture_id,
+                            },
+                    } => {
+                        // Calculate scissor rectangle, truncated like the GPU one
+                        let [min_x, min_y, max_x, max_y] = coordinates.clip_rect_to_pixels(clip_rect);
+                        let scissor = Scissor {
+                            min_x: min_x.max(0),
+                            min_y: min_y.max(0),
+                            max_x: max_x.min(target.width as i32),
+                            max_y: max_y.min(target.height as i32),
+                        };
+
+                        // Skip if scissor is invalid
+                        if scissor.min_x >= scissor.max_x || scissor.min_y >= scissor.max_y {
+                            continue;
+                        }
+
+                        // Skip draws referencing textures that were unregistered
+                        let Some(Some(texture)) = self.textures.get(texture_id.id()) else {
+                            continue;
+                        };
+
+                        for triangle in indices[idx_offset..idx_offset + count].chunks_exact(3) {
+                            let vertex = |i: usize| {
+                                let v = &vertices[vtx_offset + triangle[i] as usize];
+                                RasterVertex::new(v, &coordinates)
+                            };
+                            rasterize_triangle(
+                                target,
+                                texture,
+                                self.filter,
+                                &scissor,
+                                [vertex(0), vertex(1), vertex(2)],
+                            );
+                        }
+                    }
+
+                    imgui::DrawCmd::RawCallback { callback, raw_cmd } => unsafe {
+                        callback(draw_list.raw(), raw_cmd);
+                    },
+
+                    _ => {}
+                }
+            }
+        }
+
+        self.release_frame_textures();
+    }
+}
+
+impl RendererBackend for SoftwareRenderer {
+    type Device = ();
+    type Texture = Image;
+    type Target<'a> = &'a mut Image;
+
+    fn init(&mut self, _device: &(), imgui_context: &mut imgui::Context) -> Result<(), Box<dyn Error>> {
+        imgui_context
+            .io_mut()
+            .backend_flags
+            .insert(BackendFlags::RENDERER_HAS_VTX_OFFSET);
+        self.reload_font_texture(imgui_context);
+        Ok(())
+    }
+
+    fn register_texture(&mut self, texture: Image) -> TextureId {
+        SoftwareRenderer::register_texture(self, texture)
+    }
+
+    fn push_texture(&mut self, texture: Image) -> TextureId {
+        SoftwareRenderer::push_texture(self, texture)
+    }
+
+    fn unregister_texture(&mut self, id: TextureId) -> Option<Image> {
+        SoftwareRenderer::unregister_texture(self, id)
+    }
+
+    fn render_draw_data(&mut self, target: &mut Image, draw_data: &DrawData) -> Result<(), Box<dyn Error>> {
+        SoftwareRenderer::render_draw_data(self, target, draw_data);
+        Ok(())
+    }
+
+    /// Nothing is ever lost, the font atlas is only copied again.
+    fn recreate_device_objects(
+        &mut self,
+        _device: &(),
+        imgui_context: &mut imgui::Context,
+    ) -> Result<(), Box<dyn Error>> {
+        self.reload_font_texture(imgui_context);
+        Ok(())
+    }
+}
+
+impl SoftwareRenderer {
+    /// Releases the textures pushed for the current frame only.
+    fn release_frame_textures(&mut self) {
+        for id in self.frame_textures.drain(..) {
+            self.textures[id.id()] = None;
+        }
+    }
+}
+
+/// Pixels drawn to, from `min` included to `max` excluded.
+struct Scissor {
+    min_x: i32,
+    min_y: i32,
+    max_x: i32,
+    max_y: i32,
+}
+
+/// A vertex in framebuffer pixels, with its color as floats.
+struct RasterVertex {
+    pos: [f32; 2],
+    uv: [f32; 2],
+    col: [f32; 4],
+}
+
+impl RasterVertex {
+    fn new(vertex: &DrawVert, coordinates: &Coordinates) -> Self {
+        Self {
+            pos: coordinates.display_to_pixels(vertex.pos),
+            uv: vertex.uv,
+            col: vertex.col.map(|c| c as f32 / 255.0),
+        }
+    }
+}
+
+/// Signed area of the parallelogram `a, b, p`, positive when `p` is on the right of `a -> b`
+/// (with y pointing down).
+fn edge(a: [f32; 2], b: [f32; 2], p: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+}
+
+/// Whether pixels exactly on `a -> b` belong to the triangle, so that pixels shared by
+/// adjacent triangles are only drawn once.
+fn is_top_left(a: [f32; 2], b: [f32; 2]) -> bool {
+    (a[1] == b[1] && b[0] > a[0]) || b[1] < a[1]
+}
+
+fn rasterize_triangle(target: &mut Image, texture: &Image, filter: Filter, scissor: &Scissor, v: [RasterVertex; 3]) {
+    // Triangles are not culled, bring them all to the same winding
+    let [v0, mut v1, mut v2] = v;
+    let mut area = edge(v0.pos, v1.pos, v2.pos);
+    if area < 0.0 {
+        std::mem::swap(&mut v1, &mut v2);
+        area = -area;
+    }
+    if area == 0.0 {
+        return;
+    }
+
+    let min_x = (v0.pos[0].min(v1.pos[0]).min(v2.pos[0]).floor() as i32).max(scissor.min_x);
+    let min_y = (v0.pos[1].min(v1.pos[1]).min(v2.pos[1]).floor() as i32).max(scissor.min_y);
+    let max_x = (v0.pos[0].max(v1.pos[0]).max(v2.pos[0]).ceil() as i32).min(scissor.max_x);
+    let max_y = (v0.pos[1].max(v1.pos[1]).max(v2.pos[1]).ceil() as i32).min(scissor.max_y);
+
+    let edges = [(&v1, &v2), (&v2, &v0), (&v0, &v1)];
+
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let p = [x as f32 + 0.5, y as f32 + 0.5];
+
+            // Weight of each vertex, from the edge in front of it
+            let mut weights = [0.0; 3];
+            let inside = edges.iter().zip(&mut weights).all(|((a, b), weight)| {
+                *weight = edge(a.pos, b.pos, p);
+                *weight > 0.0 || (*weight == 0.0 && is_top_left(a.pos, b.pos))
+            });
+            if !inside {
+                continue;
+            }
+
+            let [w0, w1, w2] = weights.map(|weight| weight / area);
+            let interpolate = |a: f32, b: f32, c: f32| a * w0 + b * w1 + c * w2;
+
+            let uv = [
+                interpolate(v0.uv[0], v1.uv[0], v2.uv[0]),
+                interpolate(v0.uv[1], v1.uv[1], v2.uv[1]),
+            ];
+            let texel = sample(texture, filter, uv);
+
+            let mut color = [0.0; 4];
+            for (i, channel) in color.iter_mut().enumerate() {
+                *channel = interpolate(v0.col[i], v1.col[i], v2.col[i]) * texel[i];
+            }
+
+            blend(target, x as u32, y as u32, color);
+        }
+    }
+}
+
+/// Samples a texture with clamp-to-edge addressing, like the default sampler.
+fn sample(texture: &Image, filter: Filter, [u, v]: [f32; 2]) -> [f32; 4] {
+    if texture.width == 0 || texture.height == 0 {
+        return [0.0; 4];
+    }
+
+    let texel = |x: i64, y: i64| {
+        let x = x.clamp(0, texture.width as i64 - 1) as u32;
+        let y = y.clamp(0, texture.height as i64 - 1) as u32;
+        texture.pixel(x, y).map(|c| c as f32 / 255.0)
+    };
+
+    let x = u * texture.width as f32;
+    let y = v * texture.height as f32;
+
+    match filter {
+        Filter::Nearest => texel(x.floor() as i64, y.floor() as i64),
+        Filter::Linear => {
+            // Texel centers are at half coordinates
+            let (x, y) = (x - 0.5, y - 0.5);
+            let (x0, y0) = (x.floor(), y.floor());
+            let (fx, fy) = (x - x0, y - y0);
+            let (x0, y0) = (x0 as i64, y0 as i64);
+
+            let [t00, t10, t01, t11] = [
+                texel(x0, y0),
+                texel(x0 + 1, y0),
+                texel(x0, y0 + 1),
+                texel(x0 + 1, y0 + 1),
+            ];
+
+            let mut color = [0.0; 4];
+            for (i, channel) in color.iter_mut().enumerate() {
+                let top = t00[i] + (t10[i] - t00[i]) * fx;
+                let bottom = t01[i] + (t11[i] - t01[i]) * fx;
+                *channel = top + (bottom - top) * fy;
+            }
+            color
+        }
+    }
+}
+
+/// Blends a straight color over a pixel, with the blend state of the GPU pipeline.
+fn blend(target: &mut Image, x: u32, y: u32, [r, g, b, a]: [f32; 4]) {
+    let offset = (y as usize * target.width as usize + x as usize) * 4;
+    let pixel = &mut target.data[offset..offset + 4];
+
+    let dst = |i: usize| pixel[i] as f32 / 255.0;
+    let blended = [
+        r * a + dst(0) * (1.0 - a),
+        g * a + dst(1) * (1.0 - a),
+        b * a + dst(2) * (1.0 - a),
+        a + dst(3) * (1.0 - a),
+    ];
+
+    // Like UNORM targets, round to the nearest value
+    for (channel, value) in pixel.iter_mut().zip(blended) {
+        *channel = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    }
+}
+
+/// Copies the ImGui font atlas, the same bytes the GPU renderer uploads.
+fn create_font_texture(imgui_context: &mut imgui::Context) -> Image {
+    let font_atlas = imgui_context.fonts().build_rgba32_texture();
+    let texture = Image {
+        width: font_atlas.width,
+        height: font_atlas.height,
+        data: font_atlas.data.to_vec(),
+    };
+
+    // Assign the font texture ID (hardcoded to 0)
+    imgui_context.fonts().tex_id = TextureId::from(0);
+
+    texture
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: [u8; 4] = [255; 4];
+
+    fn vertex(pos: [f32; 2], col: [f32; 4]) -> RasterVertex {
+        RasterVertex {
+            pos,
+            uv: [0.0, 0.0],
+            col,
+        }
+    }
+
+    fn full_scissor(target: &Image) -> Scissor {
+        Scissor {
+            min_x: 0,
+            min_y: 0,
+            max_x: target.width as i32,
+            max_y: target.height as i32,
+        }
+    }
+
+    /// Draws the quad `min..max` as the two triangles ImGui emits for a rectangle.
+    fn draw_quad(target: &mut Image, scissor: &Scissor, min: [f32; 2], max: [f32; 2], col: [f32; 4]) {
+        let texture = Image::from_rgba(1, 1, WHITE.to_vec()).unwrap();
+        let [a, b, c, d] = [min, [max[0], min[1]], max, [min[0], max[1]]];
+        for [p0, p1, p2] in [[a, b, c], [a, c, d]] {
+            let triangle = [vertex(p0, col), vertex(p1, col), vertex(p2, col)];
+            rasterize_triangle(target, &texture, Filter::Nearest, scissor, triangle);
+        }
+    }
+
+    #[test]
+    fn unregistered_frame_texture_slot_is_reused() {
+        let _lock = crate::test_utils::imgui_lock();
+        let mut context = imgui::Context::create();
+        let mut renderer = SoftwareRenderer::new(&mut context);
+
+        let pushed = renderer.push_texture(Image::new(1, 1));
+        assert!(renderer.unregister_texture(pushed).is_some());
+        let registered = renderer.register_texture(Image::new(2, 2));
+        assert_eq!(registered, pushed);
+
+        // The end of the frame only releases textures still pushed for it
+        renderer.release_frame_textures();
+        assert_eq!(renderer.texture_mut(registered).map(|image| image.width()), Some(2));
+
+        let pushed = renderer.push_texture(Image::new(1, 1));
+        renderer.release_frame_textures();
+        assert!(renderer.texture_mut(pushed).is_none());
+    }
+
+    #[test]
+    fn solid_quad() {
+        let mut target = Image::new(8, 8);
+        let scissor = full_scissor(&target);
+        draw_quad(&mut target, &scissor, [2.0, 2.0], [6.0, 5.0], [1.0, 0.0, 0.0, 1.0]);
+
+        for y in 0..8 {
+            for x in 0..8 {
+                let inside = (2..6).contains(&x) && (2..5).contains(&y);
+                let expected = if inside { [255, 0, 0, 255] } else { [0; 4] };
+                assert_eq!(target.pixel(x, y), expected, "pixel ({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn scissor_clips() {
+        let mut target = Image::new(8, 8);
+        let scissor = Scissor {
+            min_x: 3,
+            min_y: 1,
+            max_x: 5,
+            max_y: 7,
+        };
+        draw_quad(&mut target, &scissor, [0.0, 0.0], [8.0, 8.0], [1.0; 4]);
+
+        for y in 0..8 {
+            for x in 0..8 {
+                let inside = (3..5).contains(&x) && (1..7).contains(&y);
+                let expected = if inside { WHITE } else { [0; 4] };
+                assert_eq!(target.pixel(x, y), expected, "pixel ({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn blend_matches_gpu_equation() {
+        // Color: SrcAlpha, OneMinusSrcAlpha. Alpha: One, OneMinusSrcAlpha.
+        let mut target = Image::from_rgba(1, 1, vec![0, 100, 200, 128]).unwrap();
+        let (src, a) = ([1.0, 0.5, 0.0], 0.25);
+        blend(&mut target, 0, 0, [src[0], src[1], src[2], a]);
+
+        let dst = [0.0, 100.0 / 255.0, 200.0 / 255.0, 128.0 / 255.0];
+        let expected = [
+            src[0] * a + dst[0] * (1.0 - a),
+            src[1] * a + dst[1] * (1.0 - a),
+            src[2] * a + dst[2] * (1.0 - a),
+            a + dst[3] * (1.0 - a),
+        ]
+        .map(|value: f32| (value * 255.0).round() as u8);
+        assert_eq!(target.pixel(0, 0), expected);
+    }
+
+    #[test]
+    fn shared_edges_are_drawn_once() {
+        // The diagonal of the quad goes through pixel centers, the quad is translucent so
+        // pixels drawn by both triangles would come out brighter.
+        let mut target = Image::new(4, 4);
+        let scissor = full_scissor(&target);
+        draw_quad(&mut target, &scissor, [0.0, 0.0], [4.0, 4.0], [1.0, 1.0, 1.0, 0.5]);
+
+        let expected = target.pixel(0, 3);
+        assert_eq!(expected, [128; 4]);
+        assert!(target.data().chunks_exact(4).all(|pixel| pixel == expected));
+
+        // Two quads sharing a vertical edge
+        let mut target = Image::new(4, 2);
+        let scissor = full_scissor(&target);
+        draw_quad(&mut target, &scissor, [0.0, 0.0], [2.5, 2.0], [1.0, 1.0, 1.0, 0.5]);
+        draw_quad(&mut target, &scissor, [2.5, 0.0], [4.0, 2.0], [1.0, 1.0, 1.0, 0.5]);
+        assert!(target.data().chunks_exact(4).all(|pixel| pixel == [128; 4]));
+    }
+}
@@ -0,0 +1,188 @@
+use sdl3::{
+    gpu::{Device, PresentMode, SwapchainComposition},
+    sys,
+    video::Window,
+};
+
+/// All present modes SDL GPU knows about, in order of preference.
+pub const PRESENT_MODES: [PresentMode; 3] = [PresentMode::Vsync, PresentMode::Mailbox, PresentMode::Immediate];
+
+/// All swapchain compositions SDL GPU knows about, in order of preference.
+pub const SWAPCHAIN_COMPOSITIONS: [SwapchainComposition; 4] = [
+    SwapchainComposition::Sdr,
+    SwapchainComposition::SdrLinear,
+    SwapchainComposition::HdrExtendedLinear,
+    SwapchainComposition::Hdr10St2084,
+];
+
+/// Returns the present modes the window's swapchain supports on this device.
+///
+/// `Vsync` is always supported.
+pub fn supported_present_modes(device: &Device, window: &Window) -> Vec<PresentMode> {
+    PRESENT_MODES
+        .into_iter()
+        .filter(|&mode| unsafe {
+            sys::gpu::SDL_WindowSupportsGPUPresentMode(
+                device.raw(),
+                window.raw(),
+                sys::gpu::SDL_GPUPresentMode(mode as i32),
+            )
+        })
+        .collect()
+}
+
+/// Returns the swapchain compositions the window supports on this device.
+///
+/// `Sdr` is always supported, HDR compositions depend on the display and the driver.
+pub fn supported_compositions(device: &Device, window: &Window) -> Vec<SwapchainComposition> {
+    SWAPCHAIN_COMPOSITIONS
+        .into_iter()
+        .filter(|&composition| unsafe {
+            sys::gpu::SDL_WindowSupportsGPUSwapchainComposition(
+                device.raw(),
+                window.raw(),
+                sys::gpu::SDL_GPUSwapchainComposition(composition as i32),
+            )
+        })
+        .collect()
+}
+
+/// Swapchain parameters of a window, along with the values it supports.
+///
+/// The settings are only a description: use `ImGuiSdl3::apply_swapchain_settings` to
+/// apply them, which also rebuilds the renderer pipeline for the new swapchain format.
+///
+/// # Example
+/// ```rust,no_run
+/// # let device: sdl3::gpu::Device = unimplemented!();
+/// # let window: sdl3::video::Window = unimplemented!();
+/// # let mut imgui: imgui_sdl3::ImGuiSdl3 = unimplemented!();
+/// # let ui: &imgui::Ui = unimplemented!();
+/// let mut settings = imgui.swapchain_settings(&device, &window);
+///
+/// // inside the UI callback
+/// settings.ui(ui);
+///
+/// // once the frame is submitted
+/// if settings.is_dirty() {
+///     imgui.apply_swapchain_settings(&device, &window, &mut settings).unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SwapchainSettings {
+    present_mode: PresentMode,
+    composition: SwapchainComposition,
+    supported_present_modes: Vec<PresentMode>,
+    supported_compositions: Vec<SwapchainComposition>,
+    dirty: bool,
+}
+
+impl SwapchainSettings {
+    /// Queries the parameters supported by the window's swapchain.
+    ///
+    /// SDL does not expose the current parameters, so `present_mode` and `composition`
+    /// are taken as the ones last applied to the window.
+    pub fn query(
+        device: &Device,
+        window: &Window,
+        present_mode: PresentMode,
+        composition: SwapchainComposition,
+    ) -> Self {
+        Self {
+            present_mode,
+            composition,
+            supported_present_modes: supported_present_modes(device, window),
+            supported_compositions: supported_compositions(device, window),
+            dirty: false,
+        }
+    }
+
+    pub fn present_mode(&self) -> PresentMode {
+        self.present_mode
+    }
+
+    pub fn composition(&self) -> SwapchainComposition {
+        self.composition
+    }
+
+    pub fn supported_present_modes(&self) -> &[PresentMode] {
+        &self.supported_present_modes
+    }
+
+    pub fn supported_compositions(&self) -> &[SwapchainComposition] {
+        &self.supported_compositions
+    }
+
+    /// Returns `true` if the settings changed since they were last applied.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Requests a present mode, returns `false` if the window does not support it.
+    pub fn set_present_mode(&mut self, present_mode: PresentMode) -> bool {
+        if !self.supported_present_modes.contains(&present_mode) {
+            return false;
+        }
+
+        self.dirty |= present_mode != self.present_mode;
+        self.present_mode = present_mode;
+        true
+    }
+
+    /// Requests a composition, returns `false` if the window does not support it.
+    pub fn set_composition(&mut self, composition: SwapchainComposition) -> bool {
+        if !self.supported_compositions.contains(&composition) {
+            return false;
+        }
+
+        self.dirty |= composition != self.composition;
+        self.composition = composition;
+        true
+    }
+
+    pub(crate) fn mark_applied(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Draws a small settings panel with one combo box per parameter.
+    ///
+    /// Returns `true` if the user picked a different value this frame.
+    pub fn ui(&mut self, ui: &imgui::Ui) -> bool {
+        let previous = (self.present_mode, self.composition);
+
+        let modes = self.supported_present_modes.clone();
+        let mut index = modes.iter().position(|&m| m == self.present_mode).unwrap_or(0);
+        if ui.combo("Present mode", &mut index, &modes, |&m| present_mode_name(m).into()) {
+            self.set_present_mode(modes[index]);
+        }
+
+        let compositions = self.supported_compositions.clone();
+        let mut index = compositions.iter().position(|&c| c == self.composition).unwrap_or(0);
+        if ui.combo("Composition", &mut index, &compositions, |&c| {
+            composition_name(c).into()
+        }) {
+            self.set_composition(compositions[index]);
+        }
+
+        previous != (self.present_mode, self.composition)
+    }
+}
+
+/// Returns a user-facing name for a present mode.
+pub fn present_mode_name(present_mode: PresentMode) -> &'static str {
+    match present_mode {
+        PresentMode::Vsync => "VSync",
+        PresentMode::Immediate => "Immediate",
+        PresentMode::Mailbox => "Mailbox",
+    }
+}
+
+/// Returns a user-facing name for a swapchain composition.
+pub fn composition_name(composition: SwapchainComposition) -> &'static str {
+    match composition {
+        SwapchainComposition::Sdr => "SDR",
+        SwapchainComposition::SdrLinear => "SDR (linear)",
+        SwapchainComposition::HdrExtendedLinear => "HDR (extended linear)",
+        SwapchainComposition::Hdr10St2084 => "HDR10 (ST 2084)",
+    }
+}
@@ -0,0 +1,9 @@
+//! Helpers shared by the unit tests.
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// imgui allows a single context at a time: tests creating one hold this lock until it is dropped.
+pub(crate) fn imgui_lock() -> MutexGuard<'static, ()> {
+    static LOCK: Mutex<()> = Mutex::new(());
+    LOCK.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,526 @@
+//! Golden-image UI tests, available with the `testing` feature.
+//!
+//! `TestHarness` runs a UI closure in a headless imgui context, feeding it scripted
+//! inputs, and renders the last frame with `software::SoftwareRenderer`. The result is
+//! compared against a reference PNG with `compare_golden`.
+//!
+//! # Example
+//! ```rust,no_run
+//! use imgui_sdl3::testing::{InputEvent, TestHarness, Tolerance, compare_golden};
+//!
+//! let mut harness = TestHarness::new(320, 240);
+//! let image = harness.run(
+//!     3,
+//!     &[(1, InputEvent::MousePos([40.0, 30.0]))],
+//!     |ui| {
+//!         ui.window("Hello").build(|| ui.text("Hello world!"));
+//!     },
+//! );
+//!
+//! compare_golden(&image, "tests/golden/hello.png", &Tolerance::default()).unwrap();
+//! ```
+//!
+//! Set the `IMGUI_SDL3_UPDATE_GOLDEN` environment variable to write the references
+//! instead of comparing against them.
+
+use std::{
+    env,
+    error::Error,
+    fmt,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+pub use crate::recording::InputEvent;
+use crate::{
+    clock::{Clock, FixedClock},
+    software::{Image, SoftwareRenderer},
+};
+use image::{ImageError, RgbaImage};
+
+/// Environment variable making `compare_golden` write the references.
+pub const UPDATE_GOLDEN_VAR: &str = "IMGUI_SDL3_UPDATE_GOLDEN";
+
+/// Default time step of every frame, so that animations and timers are reproducible.
+const FRAME_DELTA: Duration = Duration::from_micros(16_667);
+
+/// Headless imgui context rendering UI frames on the CPU.
+///
+/// The context uses the default font, a fixed time step and no ini or log files, so
+/// that renders only depend on the UI code and its inputs.
+pub struct TestHarness {
+    imgui_context: imgui::Context,
+    renderer: SoftwareRenderer,
+    /// Time source of the frame time step.
+    clock: Box<dyn Clock>,
+    /// Background the frames are rendered over.
+    clear_color: [u8; 4],
+}
+
+impl TestHarness {
+    /// Creates a harness rendering `width` x `height` frames.
+    pub fn new(width: u32, height: u32) -> Self {
+        let mut imgui_context = imgui::Context::create();
+        imgui_context.set_ini_filename(None);
+        imgui_context.set_log_filename(None);
+        imgui_context
+            .fonts()
+            .add_font(&[imgui::FontSource::DefaultFontData { config: None }]);
+
+        let io = imgui_context.io_mut();
+        io.display_size = [width as f32, height as f32];
+        io.display_framebuffer_scale = [1.0, 1.0];
+        io.config_flags |= imgui::ConfigFlags::NO_MOUSE_CURSOR_CHANGE;
+
+        let renderer = SoftwareRenderer::new(&mut imgui_context);
+
+        Self {
+            imgui_context,
+            renderer,
+            clock: Box::new(FixedClock::new(FRAME_DELTA)),
+            clear_color: [128, 128, 128, 255],
+        }
+    }
+
+    /// Sets the background the frames are rendered over, opaque gray by default.
+    pub fn with_clear_color(mut self, clear_color: [u8; 4]) -> Self {
+        self.clear_color = clear_color;
+        self
+    }
+
+    /// Replaces the fixed 60 FPS time step, e.g. with a `clock::ManualClock` to test tooltip delays.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Access the imgui context, e.g. to change the style or add fonts
+    ///
+    /// Call `reload_fonts` after changing the fonts.
+    pub fn context_mut(&mut self) -> &mut imgui::Context {
+        &mut self.imgui_context
+    }
+
+    /// Access the software renderer, e.g. to register textures
+    pub fn renderer_mut(&mut self) -> &mut SoftwareRenderer {
+        &mut self.renderer
+    }
+
+    /// Copies the font atlas to the renderer again.
+    pub fn reload_fonts(&mut self) {
+        self.renderer.reload_font_texture(&mut self.imgui_context);
+    }
+
+    /// Feeds an input to imgui, it is processed on the next frame.
+    pub fn send(&mut self, event: &InputEvent) {
+        event.apply(self.imgui_context.io_mut());
+    }
+
+    /// Runs one frame of `ui` without rendering it.
+    pub fn step<F>(&mut self, ui: F)
+    where
+        F: FnOnce(&mut imgui::Ui),
+    {
+        self.imgui_context.io_mut().update_delta_time(self.clock.delta_time());
+        ui(self.imgui_context.new_frame());
+        self.imgui_context.render();
+    }
+
+    /// Runs one frame of `ui` and renders it.
+    pub fn render<F>(&mut self, ui: F) -> Image
+    where
+        F: FnOnce(&mut imgui::Ui),
+    {
+        self.imgui_context.io_mut().update_delta_time(self.clock.delta_time());
+        ui(self.imgui_context.new_frame());
+
+        let [width, height] = self.imgui_context.io().display_size;
+        let mut image = Image::new(width as u32, height as u32);
+        image.fill(self.clear_color);
+        self.renderer.render(&mut image, &mut self.imgui_context);
+        image
+    }
+
+    /// Runs `frames` frames of `ui` and renders the last one.
+    ///
+    /// `inputs` are sent before the frame of their index, frames are counted from 0.
+    /// A few frames are usually needed for windows to settle their size.
+    pub fn run<F>(&mut self, frames: u32, inputs: &[(u32, InputEvent)], mut ui: F) -> Image
+    where
+        F: FnMut(&mut imgui::Ui),
+    {
+        let frames = frames.max(1);
+
+        for frame in 0..frames {
+            for (_, event) in inputs.iter().filter(|(at, _)| *at == frame) {
+                self.send(event);
+            }
+
+            if frame + 1 < frames {
+                self.step(&mut ui);
+            }
+        }
+
+        self.render(ui)
+    }
+}
+
+/// How far a render may be from its reference.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    /// Perceptual color difference under which pixels are considered equal, from 0 to 1.
+    pub threshold: f32,
+    /// Number of pixels allowed to differ.
+    pub max_differing_pixels: usize,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            threshold: 0.1,
+            max_differing_pixels: 0,
+        }
+    }
+}
+
+impl Tolerance {
+    /// Requires pixels to be exactly equal.
+    pub fn exact() -> Self {
+        Self {
+            threshold: 0.0,
+            max_differing_pixels: 0,
+        }
+    }
+
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn with_max_differing_pixels(mut self, max_differing_pixels: usize) -> Self {
+        self.max_differing_pixels = max_differing_pixels;
+        self
+    }
+}
+
+/// Error returned when a render does not match its reference.
+#[derive(Debug)]
+pub enum GoldenError {
+    /// The reference could not be read, or the render or diff could not be written.
+    Image(ImageError),
+    /// The reference does not exist, the render was written next to where it is expected.
+    MissingReference { reference: PathBuf, actual: PathBuf },
+    /// The render and the reference have different sizes.
+    SizeMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+        actual_path: PathBuf,
+    },
+    /// Too many pixels differ, the render and a diff image were written next to the reference.
+    Mismatch {
+        differing_pixels: usize,
+        actual: PathBuf,
+        diff: PathBuf,
+    },
+}
+
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoldenError::Image(e) => write!(f, "golden image error: {e}"),
+            GoldenError::MissingReference { reference, actual } => write!(
+                f,
+                "missing reference {}, render written to {} (set {UPDATE_GOLDEN_VAR} to create it)",
+                reference.display(),
+                actual.display()
+            ),
+            GoldenError::SizeMismatch {
+                expected,
+                actual,
+                actual_path,
+            } => write!(
+                f,
+                "expected a {}x{} render, got {}x{} (written to {})",
+                expected.0,
+                expected.1,
+                actual.0,
+                actual.1,
+                actual_path.display()
+            ),
+            GoldenError::Mismatch {
+                differing_pixels,
+                actual,
+                diff,
+            } => write!(
+                f,
+                "{differing_pixels} pixels differ from the reference, render written to {} and diff to {}",
+                actual.display(),
+                diff.display()
+            ),
+        }
+    }
+}
+
+impl Error for GoldenError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GoldenError::Image(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ImageError> for GoldenError {
+    fn from(error: ImageError) -> Self {
+        GoldenError::Image(error)
+    }
+}
+
+/// Compares a render against the reference PNG at `reference`.
+///
+/// On failure, the render is written to `<name>.actual.png` and the differing pixels
+/// are highlighted in red in `<name>.diff.png`, next to the reference. With
+/// `IMGUI_SDL3_UPDATE_GOLDEN` set, the reference is overwritten with the render instead.
+pub fn compare_golden(image: &Image, reference: impl AsRef<Path>, tolerance: &Tolerance) -> Result<(), GoldenError> {
+    let reference = reference.as_ref();
+    let actual_path = sibling_path(reference, "actual");
+
+    if env::var_os(UPDATE_GOLDEN_VAR).is_some() {
+        if let Some(parent) = reference.parent() {
+            std::fs::create_dir_all(parent).map_err(ImageError::IoError)?;
+        }
+        to_rgba_image(image).save(reference)?;
+        return Ok(());
+    }
+
+    if !reference.exists() {
+        to_rgba_image(image).save(&actual_path)?;
+        return Err(GoldenError::MissingReference {
+            reference: reference.to_path_buf(),
+            actual: actual_path,
+        });
+    }
+
+    let expected = image::open(reference)?.into_rgba8();
+    if expected.dimensions() != (image.width(), image.height()) {
+        to_rgba_image(image).save(&actual_path)?;
+        return Err(GoldenError::SizeMismatch {
+            expected: expected.dimensions(),
+            actual: (image.width(), image.height()),
+            actual_path,
+        });
+    }
+
+    let mut diff = RgbaImage::new(image.width(), image.height());
+    let mut differing_pixels = 0;
+
+    for ((expected, actual), diff) in expected
+        .pixels()
+        .zip(image.data().chunks_exact(4))
+        .zip(diff.pixels_mut())
+    {
+        let actual: [u8; 4] = actual.try_into().unwrap();
+        if perceptual_delta(expected.0, actual) > tolerance.threshold {
+            differing_pixels += 1;
+            diff.0 = [255, 0, 0, 255];
+        } else {
+            // Keep a faded copy of the reference, for context
+            let luma = (luma(expected.0) * 0.25 + 0.75) * 255.0;
+            diff.0 = [luma as u8, luma as u8, luma as u8, 255];
+        }
+    }
+
+    if differing_pixels <= tolerance.max_differing_pixels {
+        return Ok(());
+    }
+
+    let diff_path = sibling_path(reference, "diff");
+    to_rgba_image(image).save(&actual_path)?;
+    diff.save(&diff_path)?;
+
+    Err(GoldenError::Mismatch {
+        differing_pixels,
+        actual: actual_path,
+        diff: diff_path,
+    })
+}
+
+/// Returns `<name>.<suffix>.png` next to `path`.
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{stem}.{suffix}.png"))
+}
+
+fn to_rgba_image(image: &Image) -> RgbaImage {
+    RgbaImage::from_raw(image.width(), image.height(), image.data().to_vec()).unwrap()
+}
+
+/// Blends a straight color over white, as pixels would be seen in an image viewer.
+fn blend_over_white([r, g, b, a]: [u8; 4]) -> [f32; 3] {
+    let a = a as f32 / 255.0;
+    [r, g, b].map(|c| 1.0 + (c as f32 / 255.0 - 1.0) * a)
+}
+
+fn luma(pixel: [u8; 4]) -> f32 {
+    let [r, g, b] = blend_over_white(pixel);
+    0.299 * r + 0.587 * g + 0.114 * b
+}
+
+/// Perceptual difference between two colors from 0 to 1, measured in the YIQ color space
+/// (Kotsarenko and Ramos, "Measuring perceived color difference using YIQ NTSC transmission
+/// color space in mobile applications").
+fn perceptual_delta(a: [u8; 4], b: [u8; 4]) -> f32 {
+    if a == b {
+        return 0.0;
+    }
+
+    let yiq = |[r, g, b]: [f32; 3]| {
+        [
+            0.298_895 * r + 0.586_622 * g + 0.114_482 * b,
+            0.595_978 * r - 0.274_176 * g - 0.321_802 * b,
+            0.211_470 * r - 0.522_617 * g + 0.311_147 * b,
+        ]
+    };
+    let [y1, i1, q1] = yiq(blend_over_white(a));
+    let [y2, i2, q2] = yiq(blend_over_white(b));
+
+    let (y, i, q) = (y1 - y2, i1 - i2, q1 - q2);
+    let delta = 0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q;
+
+    // Largest possible delta, between black and white
+    (delta / 0.5053).sqrt().min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns an empty directory for the goldens of a test.
+    fn golden_dir(test: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("imgui-sdl3-{test}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn solid(width: u32, height: u32, color: [u8; 4]) -> Image {
+        let mut image = Image::new(width, height);
+        image.fill(color);
+        image
+    }
+
+    #[test]
+    fn perceptual_delta_range() {
+        let black = [0, 0, 0, 255];
+        let white = [255; 4];
+        assert_eq!(perceptual_delta(black, black), 0.0);
+        assert!((perceptual_delta(black, white) - 1.0).abs() < 1e-3);
+        assert_eq!(perceptual_delta(black, white), perceptual_delta(white, black));
+
+        // Close colors are under the default threshold, different hues are not
+        assert!(perceptual_delta([100, 100, 100, 255], [102, 101, 100, 255]) < 0.01);
+        assert!(perceptual_delta([255, 0, 0, 255], [0, 0, 255, 255]) > Tolerance::default().threshold);
+    }
+
+    #[test]
+    fn perceptual_delta_blends_over_white() {
+        // Fully transparent pixels look white whatever their color
+        assert!(perceptual_delta([0, 0, 0, 0], [255, 0, 0, 0]) < 1e-6);
+        assert!(perceptual_delta([0, 0, 0, 0], [255; 4]) < 1e-6);
+        assert!(perceptual_delta([0, 0, 0, 128], [0, 0, 0, 255]) > 0.4);
+    }
+
+    #[test]
+    fn golden_matches() {
+        let dir = golden_dir("matches");
+        let reference = dir.join("gray.png");
+        to_rgba_image(&solid(4, 4, [128, 128, 128, 255]))
+            .save(&reference)
+            .unwrap();
+
+        let image = solid(4, 4, [129, 128, 128, 255]);
+        compare_golden(&image, &reference, &Tolerance::default()).unwrap();
+        assert!(!dir.join("gray.actual.png").exists());
+
+        match compare_golden(&image, &reference, &Tolerance::exact()) {
+            Err(GoldenError::Mismatch { differing_pixels, .. }) => assert_eq!(differing_pixels, 16),
+            result => panic!("expected a mismatch, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn golden_missing_reference() {
+        let dir = golden_dir("missing");
+        let reference = dir.join("missing.png");
+        let image = solid(3, 2, [10, 20, 30, 255]);
+
+        match compare_golden(&image, &reference, &Tolerance::default()) {
+            Err(GoldenError::MissingReference {
+                reference: path,
+                actual,
+            }) => {
+                assert_eq!(path, reference);
+                assert_eq!(actual, dir.join("missing.actual.png"));
+                assert_eq!(image::open(&actual).unwrap().into_rgba8().into_raw(), image.data());
+            }
+            result => panic!("expected a missing reference, got {result:?}"),
+        }
+        assert!(!reference.exists());
+    }
+
+    #[test]
+    fn golden_size_mismatch() {
+        let dir = golden_dir("size");
+        let reference = dir.join("size.png");
+        to_rgba_image(&solid(4, 4, [0, 0, 0, 255])).save(&reference).unwrap();
+
+        match compare_golden(&solid(4, 3, [0, 0, 0, 255]), &reference, &Tolerance::default()) {
+            Err(GoldenError::SizeMismatch {
+                expected,
+                actual,
+                actual_path,
+            }) => {
+                assert_eq!(expected, (4, 4));
+                assert_eq!(actual, (4, 3));
+                assert!(actual_path.exists());
+            }
+            result => panic!("expected a size mismatch, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn golden_mismatch_writes_diff() {
+        let dir = golden_dir("mismatch");
+        let reference = dir.join("mismatch.png");
+        to_rgba_image(&solid(4, 4, [255; 4])).save(&reference).unwrap();
+
+        let mut data = solid(4, 4, [255; 4]).into_data();
+        data[(2 * 4 + 1) * 4..(2 * 4 + 1) * 4 + 4].copy_from_slice(&[0, 0, 0, 255]);
+        data[(3 * 4 + 3) * 4..(3 * 4 + 3) * 4 + 4].copy_from_slice(&[0, 0, 255, 255]);
+        let image = Image::from_rgba(4, 4, data).unwrap();
+
+        // Up to `max_differing_pixels` pixels may differ
+        let tolerance = Tolerance::default().with_max_differing_pixels(2);
+        compare_golden(&image, &reference, &tolerance).unwrap();
+        assert!(!dir.join("mismatch.diff.png").exists());
+
+        match compare_golden(&image, &reference, &Tolerance::default().with_max_differing_pixels(1)) {
+            Err(GoldenError::Mismatch {
+                differing_pixels,
+                actual,
+                diff,
+            }) => {
+                assert_eq!(differing_pixels, 2);
+                assert_eq!(image::open(&actual).unwrap().into_rgba8().into_raw(), image.data());
+
+                let diff = image::open(&diff).unwrap().into_rgba8();
+                assert_eq!(diff.dimensions(), (4, 4));
+                for (x, y, pixel) in diff.enumerate_pixels() {
+                    let differs = (x, y) == (1, 2) || (x, y) == (3, 3);
+                    assert_eq!(pixel.0 == [255, 0, 0, 255], differs, "diff pixel ({x}, {y})");
+                }
+            }
+            result => panic!("expected a mismatch, got {result:?}"),
+        }
+    }
+}
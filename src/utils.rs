@@ -1,6 +1,9 @@
 use std::error::Error;
 
-use sdl3::gpu::{Device, TextureCreateInfo, TextureFormat, TextureType, TextureUsage, *};
+use sdl3::{
+    gpu::{Device, TextureCreateInfo, TextureFormat, TextureType, TextureUsage, *},
+    surface::SurfaceRef,
+};
 
 pub fn create_buffer_with_data<T: Copy>(
     device: &Device,
@@ -57,7 +60,8 @@ pub fn create_texture(
     width: u32,
     height: u32,
 ) -> Result<Texture<'static>, Box<dyn Error>> {
-    let size_bytes = width * height * 4; // Assuming RGBA8 format
+    let descriptor = TextureDescriptor::new(width, height, PixelFormat::Rgba8);
+    let size_bytes = descriptor.validate(image_data)?;
 
     let texture = device.create_texture(
         TextureCreateInfo::new()
@@ -70,6 +74,355 @@ pub fn create_texture(
             .with_usage(TextureUsage::SAMPLER),
     )?;
 
+    upload_texture_data(device, copy_pass, &texture, &descriptor, image_data, size_bytes)?;
+
+    Ok(texture)
+}
+
+/// Pixel formats accepted by `create_texture_with_format`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    R8,
+    Rg8,
+    #[default]
+    Rgba8,
+    Rgba8Srgb,
+    Bgra8,
+    Rgba16Float,
+    Rgba32Float,
+    Bc1,
+    Bc1Srgb,
+    Bc2,
+    Bc2Srgb,
+    Bc3,
+    Bc3Srgb,
+    Bc4,
+    Bc5,
+    Bc6hFloat,
+    Bc6hUfloat,
+    Bc7,
+    Bc7Srgb,
+}
+
+impl PixelFormat {
+    /// Returns the matching SDL GPU texture format.
+    pub fn texture_format(self) -> TextureFormat {
+        match self {
+            PixelFormat::R8 => TextureFormat::R8Unorm,
+            PixelFormat::Rg8 => TextureFormat::R8g8Unorm,
+            PixelFormat::Rgba8 => TextureFormat::R8g8b8a8Unorm,
+            PixelFormat::Rgba8Srgb => TextureFormat::R8g8b8a8UnormSrgb,
+            PixelFormat::Bgra8 => TextureFormat::B8g8r8a8Unorm,
+            PixelFormat::Rgba16Float => TextureFormat::R16g16b16a16Float,
+            PixelFormat::Rgba32Float => TextureFormat::R32g32b32a32Float,
+            PixelFormat::Bc1 => TextureFormat::Bc1RgbaUnorm,
+            PixelFormat::Bc1Srgb => TextureFormat::Bc1RgbaUnormSrgb,
+            PixelFormat::Bc2 => TextureFormat::Bc2RgbaUnorm,
+            PixelFormat::Bc2Srgb => TextureFormat::Bc2RgbaUnormSrgb,
+            PixelFormat::Bc3 => TextureFormat::Bc3RgbaUnorm,
+            PixelFormat::Bc3Srgb => TextureFormat::Bc3RgbaUnormSrgb,
+            PixelFormat::Bc4 => TextureFormat::Bc4RUnorm,
+            PixelFormat::Bc5 => TextureFormat::Bc5RgUnorm,
+            PixelFormat::Bc6hFloat => TextureFormat::Bc6hRgbFloat,
+            PixelFormat::Bc6hUfloat => TextureFormat::Bc6hRgbUfloat,
+            PixelFormat::Bc7 => TextureFormat::Bc7RgbaUnorm,
+            PixelFormat::Bc7Srgb => TextureFormat::Bc7RgbaUnormSrgb,
+        }
+    }
+
+    /// Returns `true` for block-compressed formats, which are stored in 4x4 texel blocks.
+    pub fn is_compressed(self) -> bool {
+        self.block_dimension() > 1
+    }
+
+    /// Returns `true` if the sampler returns linear values, either because the GPU decodes
+    /// sRGB on sampling or because the format stores floating point data.
+    pub fn is_linear(self) -> bool {
+        matches!(
+            self,
+            PixelFormat::Rgba8Srgb
+                | PixelFormat::Rgba16Float
+                | PixelFormat::Rgba32Float
+                | PixelFormat::Bc1Srgb
+                | PixelFormat::Bc2Srgb
+                | PixelFormat::Bc3Srgb
+                | PixelFormat::Bc6hFloat
+                | PixelFormat::Bc6hUfloat
+                | PixelFormat::Bc7Srgb
+        )
+    }
+
+    /// Width and height of a block of texels, 1 for uncompressed formats.
+    pub fn block_dimension(self) -> u32 {
+        match self {
+            PixelFormat::R8
+            | PixelFormat::Rg8
+            | PixelFormat::Rgba8
+            | PixelFormat::Rgba8Srgb
+            | PixelFormat::Bgra8
+            | PixelFormat::Rgba16Float
+            | PixelFormat::Rgba32Float => 1,
+            _ => 4,
+        }
+    }
+
+    /// Size in bytes of a block of texels (or of a single texel for uncompressed formats).
+    pub fn block_size(self) -> u32 {
+        match self {
+            PixelFormat::R8 => 1,
+            PixelFormat::Rg8 => 2,
+            PixelFormat::Rgba8 | PixelFormat::Rgba8Srgb | PixelFormat::Bgra8 => 4,
+            PixelFormat::Rgba16Float => 8,
+            PixelFormat::Rgba32Float => 16,
+            PixelFormat::Bc1 | PixelFormat::Bc1Srgb | PixelFormat::Bc4 => 8,
+            _ => 16,
+        }
+    }
+
+    /// Size in bytes of a tightly packed row of blocks.
+    pub fn row_pitch(self, width: u32) -> u32 {
+        width.div_ceil(self.block_dimension()) * self.block_size()
+    }
+
+    /// Number of rows of blocks in an image.
+    pub fn row_count(self, height: u32) -> u32 {
+        height.div_ceil(self.block_dimension())
+    }
+}
+
+/// Description of a texture created with `create_texture_with_format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureDescriptor {
+    pub width: u32,
+    pub height: u32,
+    pub format: PixelFormat,
+    /// Bytes from the start of a row of blocks to the next in the source data, `None`
+    /// when rows are tightly packed.
+    pub row_pitch: Option<u32>,
+    /// Generates the full mip chain on the GPU, so that minified images do not alias.
+    ///
+    /// Not available for compressed formats, which cannot be rendered to.
+    pub generate_mipmaps: bool,
+}
+
+impl TextureDescriptor {
+    pub fn new(width: u32, height: u32, format: PixelFormat) -> Self {
+        Self {
+            width,
+            height,
+            format,
+            row_pitch: None,
+            generate_mipmaps: false,
+        }
+    }
+
+    pub fn with_row_pitch(mut self, row_pitch: u32) -> Self {
+        self.row_pitch = Some(row_pitch);
+        self
+    }
+
+    pub fn with_mipmaps(mut self, generate_mipmaps: bool) -> Self {
+        self.generate_mipmaps = generate_mipmaps;
+        self
+    }
+
+    /// Number of mip levels of the texture.
+    pub fn mip_levels(&self) -> u32 {
+        if self.generate_mipmaps {
+            u32::BITS - self.width.max(self.height).max(1).leading_zeros()
+        } else {
+            1
+        }
+    }
+
+    /// Checks the descriptor and the data length, and returns the number of bytes to upload.
+    pub fn validate(&self, data: &[u8]) -> Result<u32, Box<dyn Error>> {
+        if self.width == 0 || self.height == 0 {
+            return Err("texture dimensions must not be zero".into());
+        }
+
+        let format = self.format;
+        if self.generate_mipmaps && format.is_compressed() {
+            return Err(format!("cannot generate mipmaps for compressed format {format:?}").into());
+        }
+
+        let min_pitch = format.row_pitch(self.width);
+        let pitch = self.row_pitch.unwrap_or(min_pitch);
+        if pitch < min_pitch || !pitch.is_multiple_of(format.block_size()) {
+            return Err(format!(
+                "row pitch of {pitch} bytes is invalid for a {} texels wide {format:?} texture (at least {min_pitch}, multiple of {})",
+                self.width,
+                format.block_size()
+            )
+            .into());
+        }
+
+        // The last row does not need padding
+        let size_bytes = pitch * (format.row_count(self.height) - 1) + min_pitch;
+        if data.len() != size_bytes as usize && data.len() != (pitch * format.row_count(self.height)) as usize {
+            return Err(format!(
+                "expected {size_bytes} bytes for a {}x{} {format:?} texture, got {}",
+                self.width,
+                self.height,
+                data.len()
+            )
+            .into());
+        }
+
+        Ok(data.len() as u32)
+    }
+}
+
+/// Creates a texture of any supported format, optionally generating its mipmaps.
+///
+/// The upload and the mipmap generation are recorded into `command_buffer`, which
+/// must not have a pass in progress.
+pub fn create_texture_with_format(
+    device: &Device,
+    command_buffer: &CommandBuffer,
+    descriptor: &TextureDescriptor,
+    data: &[u8],
+) -> Result<Texture<'static>, Box<dyn Error>> {
+    let copy_pass = device.begin_copy_pass(command_buffer)?;
+    let texture = upload_texture(device, &copy_pass, descriptor, data);
+    device.end_copy_pass(copy_pass);
+    let texture = texture?;
+
+    if descriptor.generate_mipmaps {
+        generate_mipmaps(command_buffer, &texture);
+    }
+
+    Ok(texture)
+}
+
+/// Creates a texture and records the upload of its first mip level into a copy pass.
+///
+/// This allows batching several uploads in the same copy pass. Mipmaps are not generated,
+/// call `generate_mipmaps` once the copy pass has ended.
+pub fn upload_texture(
+    device: &Device,
+    copy_pass: &CopyPass,
+    descriptor: &TextureDescriptor,
+    data: &[u8],
+) -> Result<Texture<'static>, Box<dyn Error>> {
+    let size_bytes = descriptor.validate(data)?;
+
+    let mut usage = TextureUsage::SAMPLER;
+    if descriptor.generate_mipmaps {
+        // Mipmaps are generated by blitting each level into the next one
+        usage = usage | TextureUsage::COLOR_TARGET;
+    }
+
+    let format = descriptor.format.texture_format();
+    let supported = unsafe {
+        sdl3::sys::gpu::SDL_GPUTextureSupportsFormat(
+            device.raw(),
+            sdl3::sys::gpu::SDL_GPUTextureFormat(format as i32),
+            sdl3::sys::gpu::SDL_GPUTextureType(TextureType::_2D as i32),
+            usage.0,
+        )
+    };
+    if !supported {
+        return Err(format!("{:?} textures are not supported by this device", descriptor.format).into());
+    }
+
+    let texture = device.create_texture(
+        TextureCreateInfo::new()
+            .with_format(format)
+            .with_type(TextureType::_2D)
+            .with_width(descriptor.width)
+            .with_height(descriptor.height)
+            .with_layer_count_or_depth(1)
+            .with_num_levels(descriptor.mip_levels())
+            .with_usage(usage),
+    )?;
+
+    upload_texture_data(device, copy_pass, &texture, descriptor, data, size_bytes)?;
+
+    Ok(texture)
+}
+
+/// Fills the mip chain of a texture from its first level.
+///
+/// The texture must have been created with `generate_mipmaps`, and `command_buffer`
+/// must not have a pass in progress.
+pub fn generate_mipmaps(command_buffer: &CommandBuffer, texture: &Texture) {
+    unsafe { sdl3::sys::gpu::SDL_GenerateMipmapsForGPUTexture(command_buffer.raw(), texture.raw()) };
+}
+
+/// Creates a sampler suited to UI images.
+///
+/// `Filter::Linear` smooths photos and icons (with trilinear mipmapping), `Filter::Nearest`
+/// keeps the hard edges of pixel art.
+pub fn create_sampler(device: &Device, filter: Filter) -> Result<Sampler, sdl3::Error> {
+    let mipmap_mode = match filter {
+        Filter::Nearest => SamplerMipmapMode::Nearest,
+        Filter::Linear => SamplerMipmapMode::Linear,
+    };
+
+    device.create_sampler(
+        SamplerCreateInfo::new()
+            .with_min_filter(filter)
+            .with_mag_filter(filter)
+            .with_mipmap_mode(mipmap_mode)
+            .with_address_mode_u(SamplerAddressMode::ClampToEdge)
+            .with_address_mode_v(SamplerAddressMode::ClampToEdge)
+            .with_address_mode_w(SamplerAddressMode::ClampToEdge),
+    )
+}
+
+/// Decoded pixels, ready to be passed to `create_texture_with_format`.
+pub struct DecodedImage {
+    pub descriptor: TextureDescriptor,
+    pub data: Vec<u8>,
+}
+
+/// Creates a texture from an SDL surface of any pixel format.
+///
+/// Float surfaces are uploaded as float textures, every other format is converted to
+/// RGBA8. The surface pitch is respected, so padded rows need no repacking. Returns the
+/// texture along with the format it was created with.
+pub fn create_texture_from_surface(
+    device: &Device,
+    command_buffer: &CommandBuffer,
+    surface: &SurfaceRef,
+    generate_mipmaps: bool,
+) -> Result<(Texture<'static>, PixelFormat), Box<dyn Error>> {
+    let (format, converted) = match surface.pixel_format() {
+        sdl3::pixels::PixelFormat::RGBA32 => (PixelFormat::Rgba8, None),
+        sdl3::pixels::PixelFormat::RGBA64_FLOAT => (PixelFormat::Rgba16Float, None),
+        sdl3::pixels::PixelFormat::RGBA128_FLOAT => (PixelFormat::Rgba32Float, None),
+        _ => (
+            PixelFormat::Rgba8,
+            Some(surface.convert_format(sdl3::pixels::PixelFormat::RGBA32)?),
+        ),
+    };
+    let surface = converted.as_deref().unwrap_or(surface);
+
+    let descriptor = TextureDescriptor::new(surface.width(), surface.height(), format)
+        .with_row_pitch(surface.pitch())
+        .with_mipmaps(generate_mipmaps);
+
+    // Only the rows are needed, the lock may expose a longer buffer
+    let size = (surface.pitch() * surface.height()) as usize;
+    let texture = surface.with_lock(|pixels| {
+        create_texture_with_format(device, command_buffer, &descriptor, &pixels[..size.min(pixels.len())])
+    })?;
+
+    Ok((texture, format))
+}
+
+/// Copies validated data into a new transfer buffer and uploads it to the first mip level.
+fn upload_texture_data(
+    device: &Device,
+    copy_pass: &CopyPass,
+    texture: &Texture,
+    descriptor: &TextureDescriptor,
+    data: &[u8],
+    size_bytes: u32,
+) -> Result<(), Box<dyn Error>> {
+    let format = descriptor.format;
+
     let transfer_buffer = device
         .create_transfer_buffer()
         .with_size(size_bytes)
@@ -77,21 +430,27 @@ pub fn create_texture(
         .build()?;
 
     let mut buffer_mem = transfer_buffer.map::<u8>(device, false);
-    buffer_mem.mem_mut().copy_from_slice(image_data);
+    buffer_mem.mem_mut().copy_from_slice(data);
     buffer_mem.unmap();
 
+    // SDL expects the row length in texels, which must cover whole blocks
+    let pitch = descriptor.row_pitch.unwrap_or(format.row_pitch(descriptor.width));
+    let pixels_per_row = pitch / format.block_size() * format.block_dimension();
+
     copy_pass.upload_to_gpu_texture(
         TextureTransferInfo::new()
             .with_transfer_buffer(&transfer_buffer)
-            .with_offset(0),
+            .with_offset(0)
+            .with_pixels_per_row(pixels_per_row)
+            .with_rows_per_layer(format.row_count(descriptor.height) * format.block_dimension()),
         TextureRegion::new()
-            .with_texture(&texture)
+            .with_texture(texture)
             .with_layer(0)
-            .with_width(width)
-            .with_height(height)
+            .with_width(descriptor.width)
+            .with_height(descriptor.height)
             .with_depth(1),
         false,
     );
 
-    Ok(texture)
+    Ok(())
 }
@@ -0,0 +1,460 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    ffi::c_void,
+    sync::{Mutex, PoisonError},
+};
+
+use imgui::MouseCursor;
+use sdl3::{
+    Sdl,
+    mouse::{Cursor, MouseUtil, SystemCursor},
+    pixels::PixelFormat,
+    rect::Rect,
+    surface::Surface,
+    sys::events::{
+        SDL_AddEventWatch, SDL_EVENT_DROP_BEGIN, SDL_EVENT_DROP_FILE, SDL_EVENT_DROP_POSITION, SDL_EVENT_DROP_TEXT,
+        SDL_Event, SDL_RemoveEventWatch,
+    },
+    video::Window,
+};
+
+use crate::{platform::CUSTOM_CURSOR, software::Image};
+
+/// Identifies a custom cursor registered with `Platform::register_cursor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CursorId(pub(crate) u32);
+
+/// A custom color cursor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CursorImage {
+    pub image: Image,
+    /// Pixel of the image pointing at the mouse position.
+    pub hotspot: [i32; 2],
+}
+
+/// Cursor requested by imgui.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CursorShape<'a> {
+    System(MouseCursor),
+    Custom(CursorId, &'a CursorImage),
+}
+
+/// Requests a custom cursor from imgui code, for the current frame.
+///
+/// Like `Ui::set_mouse_cursor`, it must be called every frame the cursor is wanted.
+/// imgui itself gets the arrow, which it draws while it draws the cursor
+/// (`Io::mouse_draw_cursor`): custom cursors are only shown by the system.
+pub fn set_custom_cursor(ui: &imgui::Ui, id: CursorId) {
+    ui.set_mouse_cursor(Some(MouseCursor::Arrow));
+    CUSTOM_CURSOR.set(Some(id));
+}
+
+/// The window and input state `Platform::prepare_frame_with` reads each frame.
+///
+/// `SdlWindow` implements it for an SDL window, `MockWindow` for tests without any
+/// video driver.
+pub trait PlatformWindow {
+    /// Size in logical pixels (screen coordinates).
+    fn size(&self) -> (u32, u32);
+
+    /// Size of the drawable area in physical pixels.
+    fn size_in_pixels(&self) -> (u32, u32);
+
+    /// Content (DPI) scale of the display the window is on.
+    fn content_scale(&self) -> f32;
+
+    /// Mouse position relative to the window, in logical pixels.
+    fn mouse_position(&self) -> [f32; 2];
+
+    /// Mouse position relative to the window while a drag from the OS hovers it, in logical pixels.
+    fn drop_position(&self) -> [f32; 2];
+
+    /// Whether the mouse is over the window, or captured by it.
+    fn has_mouse_focus(&self) -> bool;
+
+    /// Whether the application enabled relative mouse mode on the window.
+    fn relative_mouse_mode(&self) -> bool;
+
+    /// Moves the mouse to a position relative to the window, in logical pixels.
+    fn warp_mouse(&mut self, position: [f32; 2]);
+
+    /// Moves the IME candidate window next to the text being edited, in logical pixels.
+    fn set_text_input_area(&mut self, position: [f32; 2], line_height: f32);
+
+    /// Shows the given cursor, or hides the cursor.
+    ///
+    /// Called every frame, implementations should only act on changes.
+    fn set_cursor(&mut self, cursor: Option<CursorShape<'_>>);
+}
+
+/// Cursor shown by `SdlWindow`, without the image of custom cursors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CursorKey {
+    System(MouseCursor),
+    Custom(CursorId),
+}
+
+/// `PlatformWindow` implementation for an SDL window.
+///
+/// SDL cursors are created on first use and kept, so that switching cursors is cheap.
+pub struct SdlWindow {
+    window: Window,
+    mouse: MouseUtil,
+    /// System cursors indexed by `MouseCursor`.
+    system_cursors: [Option<Cursor>; MouseCursor::COUNT],
+    /// `None` for custom cursors SDL failed to create, which show the arrow instead.
+    custom_cursors: HashMap<CursorId, Option<Cursor>>,
+    /// Cursor currently set, `Some(None)` once hidden.
+    current: Option<Option<CursorKey>>,
+    /// Boxed so that its address, given to SDL, stays the same.
+    drop_watch: Box<DropWatch>,
+}
+
+impl SdlWindow {
+    pub fn new(sdl: &Sdl, window: &Window) -> Self {
+        let drop_watch = Box::new(DropWatch {
+            window_id: window.id(),
+            position: Mutex::new(None),
+        });
+        unsafe { SDL_AddEventWatch(Some(watch_drop_position), drop_watch.userdata()) };
+
+        Self {
+            window: window.clone(),
+            mouse: sdl.mouse(),
+            system_cursors: Default::default(),
+            custom_cursors: HashMap::new(),
+            current: None,
+            drop_watch,
+        }
+    }
+
+    pub fn window(&self) -> &Window {
+        &self.window
+    }
+}
+
+impl PlatformWindow for SdlWindow {
+    fn size(&self) -> (u32, u32) {
+        self.window.size()
+    }
+
+    fn size_in_pixels(&self) -> (u32, u32) {
+        self.window.size_in_pixels()
+    }
+
+    fn content_scale(&self) -> f32 {
+        self.window
+            .get_display()
+            .and_then(|d| d.get_content_scale())
+            .unwrap_or(1.0)
+    }
+
+    fn mouse_position(&self) -> [f32; 2] {
+        let (mut x, mut y) = (0.0, 0.0);
+        // Same as `MouseState::new`, the window keeps the video subsystem alive
+        unsafe { sdl3::sys::mouse::SDL_GetMouseState(&mut x, &mut y) };
+        [x, y]
+    }
+
+    fn drop_position(&self) -> [f32; 2] {
+        // The mouse state is not updated during the drag, the drop events tell where it is
+        self.drop_watch.position().unwrap_or_else(|| self.mouse_position())
+    }
+
+    fn has_mouse_focus(&self) -> bool {
+        self.mouse.focused_window_id() == Some(self.window.id())
+    }
+
+    fn relative_mouse_mode(&self) -> bool {
+        self.mouse.relative_mouse_mode(&self.window)
+    }
+
+    fn warp_mouse(&mut self, [x, y]: [f32; 2]) {
+        self.mouse.warp_mouse_in_window(&self.window, x, y);
+    }
+
+    fn set_text_input_area(&mut self, [x, y]: [f32; 2], line_height: f32) {
+        let rect = Rect::new(x as i32, y as i32, 1, line_height.max(1.0) as u32);
+        self.window.subsystem().text_input().set_rect(&self.window, rect, 0);
+    }
+
+    fn set_cursor(&mut self, cursor: Option<CursorShape<'_>>) {
+        let key = cursor.map(|cursor| match cursor {
+            CursorShape::System(cursor) => CursorKey::System(cursor),
+            CursorShape::Custom(id, _) => CursorKey::Custom(id),
+        });
+        if self.current == Some(key) {
+            return;
+        }
+
+        let sdl_cursor = match cursor {
+            Some(CursorShape::System(cursor)) => self.system_cursor(cursor),
+            Some(CursorShape::Custom(id, image)) => {
+                // Keep a usable cursor rather than failing the frame, without trying again every frame
+                let created = self
+                    .custom_cursors
+                    .entry(id)
+                    .or_insert_with(|| create_color_cursor(image).ok())
+                    .is_some();
+                if created {
+                    self.custom_cursors[&id].as_ref()
+                } else {
+                    self.system_cursor(MouseCursor::Arrow)
+                }
+            }
+            None => None,
+        };
+
+        let shown = match sdl_cursor {
+            Some(sdl_cursor) => {
+                sdl_cursor.set();
+                true
+            }
+            None => false,
+        };
+        self.mouse.show_cursor(shown);
+        self.current = Some(key);
+    }
+}
+
+impl Drop for SdlWindow {
+    fn drop(&mut self) {
+        unsafe { SDL_RemoveEventWatch(Some(watch_drop_position), self.drop_watch.userdata()) };
+    }
+}
+
+impl SdlWindow {
+    /// Forgets the SDL cursor created for a custom cursor, e.g. once it is unregistered.
+    pub fn release_cursor(&mut self, id: CursorId) {
+        if self.current == Some(Some(CursorKey::Custom(id))) {
+            self.set_cursor(Some(CursorShape::System(MouseCursor::Arrow)));
+        }
+        self.custom_cursors.remove(&id);
+    }
+
+    fn system_cursor(&mut self, cursor: MouseCursor) -> Option<&Cursor> {
+        let slot = &mut self.system_cursors[cursor as usize];
+        if slot.is_none() {
+            *slot = Cursor::from_system(to_sdl_cursor(cursor)).ok();
+        }
+        slot.as_ref()
+    }
+}
+
+/// Position of the drag from the OS over a window, from the last drop event.
+///
+/// sdl3-rs leaves the window-relative coordinates out of its drop events, an event watch
+/// reads them from the raw events.
+struct DropWatch {
+    window_id: u32,
+    /// `None` until the drag moves over the window.
+    position: Mutex<Option<[f32; 2]>>,
+}
+
+impl DropWatch {
+    fn position(&self) -> Option<[f32; 2]> {
+        *self.position.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn userdata(&self) -> *mut c_void {
+        self as *const Self as *mut c_void
+    }
+}
+
+/// SDL event watch storing the position of drop events in the `DropWatch` given as `userdata`.
+unsafe extern "C" fn watch_drop_position(userdata: *mut c_void, event: *mut SDL_Event) -> bool {
+    let watch = unsafe { &*(userdata as *const DropWatch) };
+    let event = unsafe { &*event };
+
+    let event_type = unsafe { event.r#type };
+    let position = if event_type == SDL_EVENT_DROP_BEGIN.0 {
+        None
+    } else if [SDL_EVENT_DROP_POSITION.0, SDL_EVENT_DROP_FILE.0, SDL_EVENT_DROP_TEXT.0].contains(&event_type) {
+        let drop = unsafe { event.drop };
+        Some([drop.x, drop.y])
+    } else {
+        return true;
+    };
+
+    if unsafe { event.drop.windowID } == watch.window_id {
+        *watch.position.lock().unwrap_or_else(PoisonError::into_inner) = position;
+    }
+
+    // Watches cannot drop events, the result is ignored
+    true
+}
+
+/// `PlatformWindow` implementation with settable state, recording what the platform asks for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockWindow {
+    pub size: (u32, u32),
+    pub size_in_pixels: (u32, u32),
+    pub content_scale: f32,
+    pub mouse_position: [f32; 2],
+    pub mouse_focus: bool,
+    pub relative_mouse_mode: bool,
+    /// Positions the mouse was warped to, in order.
+    pub warps: Vec<[f32; 2]>,
+    /// Last text input area set, as its position and line height.
+    pub text_input_area: Option<([f32; 2], f32)>,
+    /// System cursor last set, `None` if hidden or custom.
+    pub cursor: Option<MouseCursor>,
+    /// Custom cursor last set.
+    pub custom_cursor: Option<CursorId>,
+}
+
+impl MockWindow {
+    /// Creates a window of `width` x `height` logical pixels, at a scale of 1.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            size: (width, height),
+            size_in_pixels: (width, height),
+            content_scale: 1.0,
+            mouse_position: [0.0, 0.0],
+            mouse_focus: true,
+            relative_mouse_mode: false,
+            warps: Vec::new(),
+            text_input_area: None,
+            cursor: Some(MouseCursor::Arrow),
+            custom_cursor: None,
+        }
+    }
+
+    /// Sets the display scale, the pixel size follows.
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.content_scale = scale;
+        self.size_in_pixels = ((self.size.0 as f32 * scale) as u32, (self.size.1 as f32 * scale) as u32);
+        self
+    }
+}
+
+impl PlatformWindow for MockWindow {
+    fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    fn size_in_pixels(&self) -> (u32, u32) {
+        self.size_in_pixels
+    }
+
+    fn content_scale(&self) -> f32 {
+        self.content_scale
+    }
+
+    fn mouse_position(&self) -> [f32; 2] {
+        self.mouse_position
+    }
+
+    fn drop_position(&self) -> [f32; 2] {
+        self.mouse_position
+    }
+
+    fn has_mouse_focus(&self) -> bool {
+        self.mouse_focus
+    }
+
+    fn relative_mouse_mode(&self) -> bool {
+        self.relative_mouse_mode
+    }
+
+    fn warp_mouse(&mut self, position: [f32; 2]) {
+        self.warps.push(position);
+        self.mouse_position = position;
+    }
+
+    fn set_text_input_area(&mut self, position: [f32; 2], line_height: f32) {
+        self.text_input_area = Some((position, line_height));
+    }
+
+    fn set_cursor(&mut self, cursor: Option<CursorShape<'_>>) {
+        (self.cursor, self.custom_cursor) = match cursor {
+            Some(CursorShape::System(cursor)) => (Some(cursor), None),
+            Some(CursorShape::Custom(id, _)) => (None, Some(id)),
+            None => (None, None),
+        };
+    }
+}
+
+/// Creates an SDL color cursor from RGBA pixels.
+fn create_color_cursor(cursor: &CursorImage) -> Result<Cursor, Box<dyn Error>> {
+    let image = &cursor.image;
+    let mut surface = Surface::new(image.width(), image.height(), PixelFormat::RGBA32)?;
+
+    // Copy row by row, the surface rows may be padded
+    let pitch = surface.pitch() as usize;
+    let row_size = image.width() as usize * 4;
+    surface.with_lock_mut(|pixels| {
+        for (dst, src) in pixels.chunks_mut(pitch).zip(image.data().chunks_exact(row_size)) {
+            dst[..row_size].copy_from_slice(src);
+        }
+    });
+
+    Ok(Cursor::from_surface(surface, cursor.hotspot[0], cursor.hotspot[1])?)
+}
+
+/// Map an imgui::MouseCursor to an equivalent sdl3::mouse::SystemCursor.
+fn to_sdl_cursor(cursor: MouseCursor) -> SystemCursor {
+    match cursor {
+        MouseCursor::Arrow => SystemCursor::Arrow,
+        MouseCursor::TextInput => SystemCursor::IBeam,
+        MouseCursor::ResizeAll => SystemCursor::SizeAll,
+        MouseCursor::ResizeNS => SystemCursor::SizeNS,
+        MouseCursor::ResizeEW => SystemCursor::SizeWE,
+        MouseCursor::ResizeNESW => SystemCursor::SizeNESW,
+        MouseCursor::ResizeNWSE => SystemCursor::SizeNWSE,
+        MouseCursor::Hand => SystemCursor::Hand,
+        MouseCursor::NotAllowed => SystemCursor::No,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sdl3::sys::events::{SDL_DropEvent, SDL_EventType, SDL_MouseMotionEvent};
+    use sdl3::sys::video::SDL_WindowID;
+
+    use super::*;
+
+    fn drop_event(event_type: SDL_EventType, window_id: u32, position: [f32; 2]) -> SDL_Event {
+        SDL_Event {
+            drop: SDL_DropEvent {
+                r#type: event_type,
+                windowID: SDL_WindowID(window_id),
+                x: position[0],
+                y: position[1],
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn drop_watch_positions() {
+        let watch = DropWatch {
+            window_id: 7,
+            position: Mutex::new(None),
+        };
+        let send = |mut event: SDL_Event| unsafe { watch_drop_position(watch.userdata(), &mut event) };
+
+        send(drop_event(SDL_EVENT_DROP_POSITION, 7, [10.0, 20.0]));
+        assert_eq!(watch.position(), Some([10.0, 20.0]));
+
+        // Other windows and events are ignored
+        send(drop_event(SDL_EVENT_DROP_POSITION, 8, [1.0, 1.0]));
+        send(SDL_Event {
+            motion: SDL_MouseMotionEvent {
+                windowID: SDL_WindowID(7),
+                x: 2.0,
+                y: 2.0,
+                ..Default::default()
+            },
+        });
+        assert_eq!(watch.position(), Some([10.0, 20.0]));
+
+        send(drop_event(SDL_EVENT_DROP_FILE, 7, [30.0, 40.0]));
+        assert_eq!(watch.position(), Some([30.0, 40.0]));
+
+        // A new drag starts without a position
+        send(drop_event(SDL_EVENT_DROP_BEGIN, 7, [0.0, 0.0]));
+        assert_eq!(watch.position(), None);
+    }
+}
@@ -0,0 +1,28 @@
+//! Golden-image tests of the software renderer, run with `--features testing`.
+//!
+//! Set `IMGUI_SDL3_UPDATE_GOLDEN` to regenerate the references in `tests/golden`.
+
+use imgui_sdl3::testing::{InputEvent, TestHarness, Tolerance, compare_golden};
+
+fn golden(name: &str) -> String {
+    format!("{}/tests/golden/{name}.png", env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn window_with_widgets() {
+    let mut harness = TestHarness::new(240, 160);
+    let mut value = 0.5;
+
+    let image = harness.run(3, &[(1, InputEvent::MousePos([60.0, 58.0]))], |ui| {
+        ui.window("Golden")
+            .position([10.0, 10.0], imgui::Condition::Always)
+            .size([220.0, 140.0], imgui::Condition::Always)
+            .build(|| {
+                ui.text("Hello world!");
+                ui.button("Hovered button");
+                ui.slider("Value", 0.0, 1.0, &mut value);
+            });
+    });
+
+    compare_golden(&image, golden("window_with_widgets"), &Tolerance::default()).unwrap();
+}